@@ -0,0 +1,109 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use maze::seg::intersection;
+use maze::*;
+use std::hint::black_box;
+
+// Random nodes, which unlike the grid give Delaunay a different edge set
+// from the nearest neighbors.
+fn build(connectivity: Connectivity) -> Maze {
+    let builder = MazeBuilder::new()
+        .radius(200.0)
+        .layout(NodeLayout::Random)
+        .target_nodes(1000)
+        .connectivity(connectivity)
+        .seed(0);
+    builder.build(&mut builder.rng()).unwrap()
+}
+
+// The candidate links each node starts out with, once each.
+fn candidate_edges(nodes: &[Node], params: &Params) -> Vec<Edge> {
+    let cache = NeighborCache::for_params(nodes, params);
+    let mut edges: Vec<Edge> = nodes
+        .iter()
+        .flat_map(|&a| {
+            cache
+                .get(a.index)
+                .iter()
+                .filter(move |b| a.index < b.index)
+                .map(move |b| Edge(a.index, b.index))
+        })
+        .collect();
+    edges.sort_by_key(|&Edge(a, b)| (a, b));
+    edges.dedup();
+    edges
+}
+
+fn crossing_count(nodes: &[Node], edges: &[Edge]) -> usize {
+    let point = |i: Index| nodes[i].point;
+    let mut count = 0;
+    for (i, &Edge(a, b)) in edges.iter().enumerate() {
+        for &Edge(c, d) in &edges[i + 1..] {
+            if intersection(point(a), point(b), point(c), point(d)) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Edge quality isn't a timing, so it is printed once up front for comparing
+// the connectivities by eye.
+fn report_quality() {
+    for connectivity in [
+        Connectivity::Knn,
+        Connectivity::Delaunay,
+        Connectivity::Gabriel,
+    ] {
+        let maze = build(connectivity);
+        let candidates = candidate_edges(&maze.nodes, &maze.params);
+        let mut lengths: Vec<f64> = maze
+            .edges
+            .iter()
+            .map(|&Edge(a, b)| (maze.nodes[a].point - maze.nodes[b].point).length())
+            .collect();
+        lengths.sort_by(f64::total_cmp);
+        let quantile = |q: f64| lengths[((lengths.len() - 1) as f64 * q) as usize];
+        println!(
+            "{connectivity:?}: {} candidate links, {} crossing; passage length \
+             min {:.2} median {:.2} p90 {:.2} max {:.2} mean {:.2}",
+            candidates.len(),
+            crossing_count(&maze.nodes, &candidates),
+            quantile(0.0),
+            quantile(0.5),
+            quantile(0.9),
+            quantile(1.0),
+            lengths.iter().sum::<f64>() / lengths.len() as f64,
+        );
+    }
+}
+
+fn bench_connectivity(c: &mut Criterion) {
+    report_quality();
+    let maze = build(Connectivity::Knn);
+    let mut group = c.benchmark_group("connectivity");
+    group.sample_size(10);
+    for connectivity in [
+        Connectivity::Knn,
+        Connectivity::Delaunay,
+        Connectivity::Gabriel,
+    ] {
+        let params = Params {
+            connectivity,
+            ..maze.params.clone()
+        };
+        group.bench_with_input(
+            BenchmarkId::new("neighbors", format!("{connectivity:?}")),
+            &params,
+            |b, params| b.iter(|| NeighborCache::for_params(black_box(&maze.nodes), params)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("build", format!("{connectivity:?}")),
+            &connectivity,
+            |b, &connectivity| b.iter(|| build(connectivity).edges.len()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_connectivity);
+criterion_main!(benches);
@@ -0,0 +1,146 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use maze::seg::intersection_with_width;
+use maze::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashSet;
+use std::hint::black_box;
+
+// A grid layout with roughly `count` nodes at the default spacing, which
+// packs about 5 000 into a radius of 360.
+fn grid_params(count: usize) -> Params {
+    Params {
+        radius: 360.0 * (count as f64 / 5000.0).sqrt(),
+        ..Default::default()
+    }
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    let params = Params::default();
+    let mut group = c.benchmark_group("generation/layouts");
+    group.sample_size(10);
+    // Given 2 seconds, though it stops once the disk is full.
+    group.bench_function("random", |b| {
+        b.iter(|| gen_nodes_random(&mut ChaCha8Rng::seed_from_u64(0), black_box(&params)).len())
+    });
+    group.bench_function("spiral", |b| {
+        b.iter(|| gen_nodes_spiral(black_box(&params)).len())
+    });
+    group.bench_function("grid", |b| {
+        b.iter(|| gen_nodes_grid(black_box(&params)).len())
+    });
+    group.bench_function("hex", |b| {
+        b.iter(|| gen_nodes_hex(black_box(&params)).len())
+    });
+    group.finish();
+}
+
+type Traversal = fn(
+    &mut ChaCha8Rng,
+    &Params,
+    V2,
+    Node,
+    &mut HashSet<Edge>,
+    &mut HashSet<Index>,
+    &[Node],
+    &NeighborCache,
+    &mut Vec<V2>,
+    &mut (usize, usize),
+    &mut std::collections::HashMap<Edge, usize>,
+    &mut dyn MazeObserver,
+);
+
+fn bench_traversals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generation/traverse");
+    group.sample_size(10);
+    for count in [500, 1000, 2000] {
+        let params = grid_params(count);
+        let nodes = gen_nodes_grid(&params);
+        let cache = NeighborCache::build(&nodes, params.neighbor_k);
+        let entry = Node {
+            point: V2 {
+                x: -params.radius,
+                y: 0.0,
+            },
+            index: 0,
+        };
+        let start = get_nearest_k(&nodes, entry, 1)[0];
+        let traversals: [(&str, Traversal); 2] = [("dfs", dfs), ("bfs", bfs)];
+        for (name, traverse) in traversals {
+            group.bench_with_input(BenchmarkId::new(name, nodes.len()), &nodes, |b, nodes| {
+                b.iter(|| {
+                    let mut edges: HashSet<Edge> = Default::default();
+                    let mut visited: HashSet<Index> = [start.index].into();
+                    traverse(
+                        &mut ChaCha8Rng::seed_from_u64(0),
+                        &params,
+                        start.point - V2 { x: 10.0, y: 0.0 },
+                        start,
+                        &mut edges,
+                        &mut visited,
+                        black_box(nodes),
+                        &cache,
+                        &mut Vec::new(),
+                        &mut (0, 0),
+                        &mut Default::default(),
+                        &mut (),
+                    );
+                    edges.len()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_nearest_k(c: &mut Criterion) {
+    let nodes = gen_nodes_grid(&Params::default());
+    let mut group = c.benchmark_group("generation/get_nearest_k");
+    for k in [6, 12, 24] {
+        group.bench_with_input(BenchmarkId::from_parameter(k), &k, |b, &k| {
+            b.iter(|| get_nearest_k(black_box(&nodes), nodes[nodes.len() / 2], k).len())
+        });
+    }
+    group.finish();
+}
+
+fn bench_intersections(c: &mut Criterion) {
+    let builder = MazeBuilder::new().params(grid_params(7000)).seed(0);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let edges = maze.sorted_edges();
+    // A passage from the middle of the maze, tested against the first `count`
+    // others; most are far away, as during generation.
+    let query = edges[edges.len() / 2];
+    let mut group = c.benchmark_group("generation/edge_intersects");
+    for count in [100, 1000, 5000] {
+        let subset: HashSet<Edge> = edges.iter().copied().take(count).collect();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &subset, |b, subset| {
+            b.iter(|| edge_intersects(&maze.params, query, black_box(subset), &maze.nodes))
+        });
+    }
+    group.finish();
+    let Edge(a, b) = query;
+    let (a, b) = (maze.nodes[a].point, maze.nodes[b].point);
+    // Parallel and overlapping, so the test runs all the way through.
+    let offset = V2 { x: 1.0, y: 1.0 };
+    c.bench_function("generation/intersection_with_width", |bench| {
+        bench.iter(|| {
+            intersection_with_width(
+                black_box(a),
+                black_box(b),
+                black_box(a + offset),
+                black_box(b + offset),
+                maze.params.tube_radius,
+            )
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_layouts,
+    bench_traversals,
+    bench_nearest_k,
+    bench_intersections
+);
+criterion_main!(benches);
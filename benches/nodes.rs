@@ -0,0 +1,82 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use maze::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::TAU;
+use std::hint::black_box;
+use std::time::Duration;
+
+// The rejection loop from `gen_nodes_random`, run until `target` nodes are
+// accepted instead of for a fixed time.
+fn random_until(rng: &mut impl Rng, params: &Params, target: usize) -> usize {
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let mut count = 0;
+    while count < target {
+        let point: V2 = Pol {
+            a: rng.gen::<f64>() * TAU,
+            r: rng.gen::<f64>() * params.node_radius(),
+        }
+        .into();
+        if !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            count += 1;
+        }
+    }
+    count
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    let params = Params {
+        radius: 250.0,
+        ..Default::default()
+    };
+    let poisson_count = gen_nodes_poisson(&mut ChaCha8Rng::seed_from_u64(0), &params, 30).len();
+    // Time for the random layout to reach the same number of nodes.
+    let target = poisson_count;
+    let budget = Params {
+        compute_time: Duration::from_millis(200),
+        ..params.clone()
+    };
+    let random_count = gen_nodes_random(&mut ChaCha8Rng::seed_from_u64(0), &budget).len();
+    let halton_count = gen_nodes_halton(&budget, usize::MAX).len();
+    eprintln!("in 200 ms: random placed {random_count} nodes, halton {halton_count}");
+    let unbounded = Params {
+        compute_time: Duration::from_secs(60),
+        ..params.clone()
+    };
+
+    let mut group = c.benchmark_group("layout");
+    group.sample_size(10);
+    group.bench_function("poisson", |b| {
+        b.iter(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            gen_nodes_poisson(&mut rng, black_box(&params), 30).len()
+        })
+    });
+    group.bench_function("random_same_density", |b| {
+        b.iter(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            random_until(&mut rng, black_box(&params), target)
+        })
+    });
+    group.bench_function("halton_same_density", |b| {
+        b.iter(|| gen_nodes_halton(black_box(&unbounded), target).len())
+    });
+    group.finish();
+}
+
+fn bench_grid(c: &mut Criterion) {
+    let params = Params::default();
+    let mut group = c.benchmark_group("grid");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter(|| gen_nodes_grid(black_box(&params)).len())
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| gen_nodes_grid_parallel(black_box(&params)).len())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_layouts, bench_grid);
+criterion_main!(benches);
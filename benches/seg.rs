@@ -0,0 +1,48 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use maze::seg::*;
+use maze::V2;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::TAU;
+use std::hint::black_box;
+
+const WIDTH: f64 = 2.5;
+const SHRINK: f64 = 0.15;
+
+// Short passages scattered over a maze-sized disk, so nearly all pairs are
+// far apart.
+fn segments() -> Vec<(V2, V2)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..1000)
+        .map(|_| {
+            let a = V2 {
+                x: rng.gen_range(-500.0..500.0),
+                y: rng.gen_range(-500.0..500.0),
+            };
+            let angle: f64 = rng.gen::<f64>() * TAU;
+            (a, a + V2::new(angle.cos(), angle.sin()) * 9.0)
+        })
+        .collect()
+}
+
+fn count(segments: &[(V2, V2)], test: impl Fn(V2, V2, V2, V2) -> bool) -> usize {
+    let (a, b) = segments[0];
+    segments.iter().filter(|&&(c, d)| test(a, b, c, d)).count()
+        + segments
+            .windows(2)
+            .filter(|w| test(w[0].0, w[0].1, w[1].0, w[1].1))
+            .count()
+}
+
+fn bench_intersection(c: &mut Criterion) {
+    let segments = segments();
+    let approx = |a, b, c, d| approx_intersection_with_width(a, b, c, d, WIDTH, SHRINK);
+    let exact = |a, b, c, d| intersection_with_width(a, b, c, d, WIDTH);
+    let mut group = c.benchmark_group("intersection_with_width");
+    group.bench_function("approx", |b| b.iter(|| count(black_box(&segments), approx)));
+    group.bench_function("exact", |b| b.iter(|| count(black_box(&segments), exact)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_intersection);
+criterion_main!(benches);
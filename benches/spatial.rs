@@ -0,0 +1,67 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use maze::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::TAU;
+use std::hint::black_box;
+
+const CANDIDATES: usize = 20_000;
+
+fn candidates(params: &Params) -> Vec<V2> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..CANDIDATES)
+        .map(|_| {
+            let radians: f64 = rng.gen::<f64>() * TAU;
+            let radius: f64 = rng.gen::<f64>() * params.node_radius();
+            V2 {
+                x: radians.cos() * radius,
+                y: radians.sin() * radius,
+            }
+        })
+        .collect()
+}
+
+fn linear_scan(params: &Params, candidates: &[V2]) -> usize {
+    let mut points: Vec<V2> = Vec::new();
+    for &point in candidates {
+        if points
+            .iter()
+            .all(|&a| (a - point).length() > params.min_spacing)
+        {
+            points.push(point);
+        }
+    }
+    points.len()
+}
+
+fn spatial_hash(params: &Params, candidates: &[V2]) -> usize {
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let mut count = 0;
+    for &point in candidates {
+        if !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            count += 1;
+        }
+    }
+    count
+}
+
+fn bench_proximity(c: &mut Criterion) {
+    let params = Params::default();
+    let candidates = candidates(&params);
+    assert_eq!(
+        linear_scan(&params, &candidates),
+        spatial_hash(&params, &candidates)
+    );
+    let mut group = c.benchmark_group("proximity");
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| linear_scan(black_box(&params), black_box(&candidates)))
+    });
+    group.bench_function("spatial_hash", |b| {
+        b.iter(|| spatial_hash(black_box(&params), black_box(&candidates)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_proximity);
+criterion_main!(benches);
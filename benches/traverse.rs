@@ -0,0 +1,78 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use maze::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::collections::HashSet;
+use std::hint::black_box;
+
+fn bench_bfs(c: &mut Criterion) {
+    // Roughly 5 000 nodes with the default spacing.
+    let params = Params {
+        radius: 360.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let cache = NeighborCache::build(&nodes, params.neighbor_k);
+    let entry = Node {
+        point: V2 {
+            x: -params.radius,
+            y: 0.0,
+        },
+        index: 0,
+    };
+    let start = get_nearest_k(&nodes, entry, 1)[0];
+    let mut group = c.benchmark_group("traverse");
+    group.sample_size(10);
+    group.bench_function("bfs_grid_5000", |b| {
+        b.iter(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let mut edges: HashSet<Edge> = Default::default();
+            let mut visited: HashSet<Index> = [start.index].into();
+            let mut midpoints: Vec<V2> = Vec::new();
+            let mut max_depth_index = (0, 0);
+            bfs(
+                &mut rng,
+                &params,
+                start.point - V2 { x: 10.0, y: 0.0 },
+                start,
+                &mut edges,
+                &mut visited,
+                black_box(&nodes),
+                &cache,
+                &mut midpoints,
+                &mut max_depth_index,
+                &mut Default::default(),
+                &mut (),
+            );
+            edges.len()
+        })
+    });
+    group.bench_function("dfs_grid_5000", |b| {
+        b.iter(|| {
+            let mut rng = ChaCha8Rng::seed_from_u64(0);
+            let mut edges: HashSet<Edge> = Default::default();
+            let mut visited: HashSet<Index> = [start.index].into();
+            let mut midpoints: Vec<V2> = Vec::new();
+            let mut max_depth_index = (0, 0);
+            dfs(
+                &mut rng,
+                &params,
+                start.point - V2 { x: 10.0, y: 0.0 },
+                start,
+                &mut edges,
+                &mut visited,
+                black_box(&nodes),
+                &cache,
+                &mut midpoints,
+                &mut max_depth_index,
+                &mut Default::default(),
+                &mut (),
+            );
+            edges.len()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_bfs);
+criterion_main!(benches);
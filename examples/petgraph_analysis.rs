@@ -0,0 +1,76 @@
+// Betweenness centrality of a maze's nodes, computed on its petgraph form
+// with Brandes' algorithm, to find the junctions most paths run through.
+//
+//     cargo run --example petgraph_analysis --features petgraph-compat
+use maze::*;
+use petgraph::visit::EdgeRef;
+use std::collections::VecDeque;
+
+fn betweenness(graph: &PetMaze) -> Vec<f64> {
+    let n = graph.node_count();
+    let mut centrality = vec![0.0; n];
+    for s in graph.node_indices() {
+        // Shortest paths by hop count from `s`.
+        let mut order = Vec::with_capacity(n);
+        let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut hops: Vec<Option<usize>> = vec![None; n];
+        paths[s.index()] = 1.0;
+        hops[s.index()] = Some(0);
+        let mut queue = VecDeque::from([s]);
+        while let Some(v) = queue.pop_front() {
+            order.push(v.index());
+            for edge in graph.edges(v) {
+                let w = if edge.source() == v {
+                    edge.target()
+                } else {
+                    edge.source()
+                };
+                let next = hops[v.index()].unwrap() + 1;
+                if hops[w.index()].is_none() {
+                    hops[w.index()] = Some(next);
+                    queue.push_back(w);
+                }
+                if hops[w.index()] == Some(next) {
+                    paths[w.index()] += paths[v.index()];
+                    preds[w.index()].push(v.index());
+                }
+            }
+        }
+        // Walk back from the farthest nodes, handing each node's share of
+        // the paths through it to its predecessors.
+        let mut dependency = vec![0.0; n];
+        for &w in order.iter().rev() {
+            for &v in &preds[w] {
+                dependency[v] += paths[v] / paths[w] * (1.0 + dependency[w]);
+            }
+            if w != s.index() {
+                centrality[w] += dependency[w];
+            }
+        }
+    }
+    // Each undirected path was counted from both ends.
+    centrality.iter().map(|c| c / 2.0).collect()
+}
+
+fn main() -> Result<()> {
+    let builder = MazeBuilder::new().radius(80.0).seed(1);
+    let maze = builder.build(&mut builder.rng())?;
+    let graph = to_petgraph(&maze);
+    let centrality = betweenness(&graph);
+    let mut ranked: Vec<(usize, f64)> = centrality.into_iter().enumerate().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    println!(
+        "nodes: {}, edges: {}",
+        graph.node_count(),
+        graph.edge_count()
+    );
+    for (i, c) in ranked.iter().take(10) {
+        let point = graph[petgraph::graph::NodeIndex::new(*i)].point;
+        println!(
+            "node {i} at ({:.1}, {:.1}): betweenness {c:.0}",
+            point.x, point.y
+        );
+    }
+    Ok(())
+}
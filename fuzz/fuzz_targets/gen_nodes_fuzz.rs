@@ -0,0 +1,34 @@
+// Places random nodes for arbitrary seeds and spacings. Run with
+//
+//     cargo +nightly fuzz run gen_nodes_fuzz
+//
+// from the repository root (install with `cargo install cargo-fuzz`).
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use maze::{gen_nodes_random, Params};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::time::Duration;
+
+fuzz_target!(|input: (u64, u8, u8, u16)| {
+    let (seed, radius, spacing, target) = input;
+    // Small disks and short runs keep each case quick.
+    let radius = 1.0 + radius as f64;
+    let params = Params {
+        radius,
+        tube_radius: radius * 0.005,
+        min_spacing: 0.1 + spacing as f64 / 8.0,
+        compute_time: Duration::from_millis(20),
+        target_nodes: (target > 0).then_some(target as usize),
+        ..Default::default()
+    };
+    let nodes = gen_nodes_random(&mut ChaCha8Rng::seed_from_u64(seed), &params);
+    for (i, node) in nodes.iter().enumerate() {
+        assert_eq!(node.index, i);
+        assert!(node.point.length() <= radius);
+    }
+    if let Some(target) = params.target_nodes {
+        assert!(nodes.len() <= target);
+    }
+});
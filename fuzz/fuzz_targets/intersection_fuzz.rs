@@ -0,0 +1,56 @@
+// Checks the segment tests against arbitrary segments. Run with
+//
+//     cargo +nightly fuzz run intersection_fuzz
+//
+// from the repository root (install with `cargo install cargo-fuzz`).
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use maze::seg::{approx_intersection_with_width, intersection_with_width};
+use maze::V2;
+
+// Far enough out to cover any maze without squaring into infinity.
+const LIMIT: f64 = 1e6;
+
+// Rounding in the distance test at LIMIT is far below this, and any real
+// passage is far wider.
+const MIN_WIDTH: f64 = 1e-6;
+
+fn coordinate(value: f64) -> Option<f64> {
+    (value.is_finite() && value.abs() <= LIMIT).then_some(value)
+}
+
+fn point((x, y): (f64, f64)) -> Option<V2> {
+    Some(V2 {
+        x: coordinate(x)?,
+        y: coordinate(y)?,
+    })
+}
+
+fuzz_target!(|input: ([(f64, f64); 4], f64, f64)| {
+    let ([a, b, c, d], width, shrink) = input;
+    let (Some(a), Some(b), Some(c), Some(d)) = (point(a), point(b), point(c), point(d)) else {
+        return;
+    };
+    let Some(width) = coordinate(width).map(f64::abs) else {
+        return;
+    };
+    let Some(shrink) = coordinate(shrink) else {
+        return;
+    };
+
+    let hit = intersection_with_width(a, b, c, d, width);
+    assert_eq!(
+        hit,
+        intersection_with_width(c, d, a, b, width),
+        "not symmetric for {a:?}-{b:?} and {c:?}-{d:?} at width {width:e}"
+    );
+    if width >= MIN_WIDTH {
+        assert!(
+            intersection_with_width(a, b, a, b, width),
+            "{a:?}-{b:?} doesn't overlap itself at width {width:e}"
+        );
+    }
+    // The old test only has to not panic.
+    approx_intersection_with_width(a, b, c, d, width, shrink);
+});
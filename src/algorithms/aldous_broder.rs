@@ -0,0 +1,106 @@
+use crate::algorithms::{components, neighbor_lists, Passages};
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Neighbors per node in the graph walked by default.
+pub const ALDOUS_BRODER_K: usize = 6;
+
+// Aldous-Broder: walk the neighbor graph at random from `start`, keeping the
+// edge used to first enter each node. Yields a uniform spanning tree, but
+// covering the graph can take many steps, so the walk gives up after
+// `nodes.len()²` of them. A step into a new node whose passage doesn't fit
+// is not taken, and a node whose every link from the tree is blocked is
+// given up on.
+pub fn gen_maze_aldous_broder(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+    neighbor_k: usize,
+) -> HashSet<Edge> {
+    let max_steps = nodes.len().saturating_mul(nodes.len());
+    gen_maze_aldous_broder_with_limit(rng, params, nodes, start, neighbor_k, max_steps)
+}
+
+pub fn gen_maze_aldous_broder_with_limit(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+    neighbor_k: usize,
+    max_steps: usize,
+) -> HashSet<Edge> {
+    let neighbors = neighbor_lists(nodes, neighbor_k);
+    let component = components(&neighbors);
+    // Only the start's component can be covered.
+    let mut remaining = component
+        .iter()
+        .filter(|&&c| c == component[start.index])
+        .count()
+        - 1;
+    let mut visited = vec![false; nodes.len()];
+    let mut passages = Passages::new(params, nodes);
+    // Links into each node not yet found blocked.
+    let mut open: Vec<usize> = neighbors.iter().map(Vec::len).collect();
+    let mut blocked: HashSet<Edge> = HashSet::new();
+    let mut u = start.index;
+    visited[u] = true;
+    let mut steps = 0;
+    while remaining > 0 {
+        if steps == max_steps {
+            log::warn!("aldous-broder: gave up after {steps} steps, {remaining} nodes unvisited");
+            break;
+        }
+        steps += 1;
+        let v = *neighbors[u].choose(rng).unwrap();
+        if !visited[v] {
+            if !passages.carve(Edge(u, v)) {
+                if blocked.insert(Edge(u, v)) {
+                    open[v] -= 1;
+                    if open[v] == 0 {
+                        remaining -= 1;
+                    }
+                }
+                continue;
+            }
+            visited[v] = true;
+            remaining -= 1;
+        }
+        u = v;
+    }
+    passages.edges
+}
+
+#[test]
+fn test_aldous_broder_spanning_tree() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    // Only the four grid neighbors, so every passage fits.
+    let edges = gen_maze_aldous_broder(&mut rng, &params, &nodes, nodes[0], 5);
+    assert_eq!(edges.len(), nodes.len() - 1);
+    let touched: HashSet<Index> = edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    assert_eq!(touched.len(), nodes.len());
+}
+
+#[test]
+fn test_aldous_broder_step_limit() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let edges =
+        gen_maze_aldous_broder_with_limit(&mut rng, &params, &nodes, nodes[0], ALDOUS_BRODER_K, 10);
+    // At most one edge per step, and never a full tree in so few steps.
+    assert!(edges.len() <= 10);
+    assert!(edges.len() < nodes.len() - 1);
+}
@@ -0,0 +1,72 @@
+use crate::algorithms::neighbor_lists;
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Removes dead ends: with probability `braid_factor`, each node left with a
+// single passage gets another one to a random nearby node it isn't already
+// joined to, as long as the new passage crosses nothing.
+pub fn braid_maze(
+    rng: &mut impl Rng,
+    params: &Params,
+    edges: &mut HashSet<Edge>,
+    nodes: &[Node],
+    braid_factor: f64,
+) {
+    let neighbors = neighbor_lists(nodes, params.neighbor_k);
+    let mut degree = vec![0; nodes.len()];
+    for &Edge(a, b) in edges.iter() {
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    let mut dead_ends: Vec<Index> = (0..nodes.len()).filter(|&u| degree[u] == 1).collect();
+    dead_ends.shuffle(rng);
+    for u in dead_ends {
+        // An earlier passage may already have reached this one.
+        if degree[u] != 1 || !rng.gen_bool(braid_factor) {
+            continue;
+        }
+        let mut candidates: Vec<Index> = neighbors[u]
+            .iter()
+            .cloned()
+            .filter(|&v| !edges.contains(&Edge(u, v)) && !edges.contains(&Edge(v, u)))
+            .collect();
+        candidates.shuffle(rng);
+        if let Some(v) = candidates
+            .into_iter()
+            .find(|&v| !edge_intersects(params, Edge(u, v), edges, nodes))
+        {
+            edges.insert(Edge(u, v));
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+    }
+}
+
+#[test]
+fn test_full_braid_joins_every_dead_end_it_can() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(4);
+    let mut edges = gen_maze_hunt_and_kill(&mut rng, &params, &nodes, nodes[0]);
+    let tree_edges = edges.len();
+    braid_maze(&mut rng, &params, &mut edges, &nodes, 1.0);
+    assert!(edges.len() > tree_edges);
+    let mut degree = vec![0; nodes.len()];
+    for &Edge(a, b) in &edges {
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    // Whatever dead ends remain have no neighbor they could be joined to.
+    let neighbors = neighbor_lists(&nodes, params.neighbor_k);
+    for u in (0..nodes.len()).filter(|&u| degree[u] == 1) {
+        assert!(neighbors[u].iter().all(|&v| edges.contains(&Edge(u, v))
+            || edges.contains(&Edge(v, u))
+            || edge_intersects(&params, Edge(u, v), &edges, &nodes)));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::algorithms::neighbor_lists;
+use crate::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Makes the maze imperfect: every pair of nearest neighbors not yet joined
+// gets a passage with probability `cycle_probability`, unless it would cross
+// an existing one. Each added passage closes a loop.
+pub fn add_cycles(
+    rng: &mut impl Rng,
+    params: &Params,
+    edges: &mut HashSet<Edge>,
+    nodes: &[Node],
+    cycle_probability: f64,
+) {
+    let neighbors = neighbor_lists(nodes, params.neighbor_k);
+    for (u, list) in neighbors.iter().enumerate() {
+        for &v in list.iter().filter(|&&v| u < v) {
+            if edges.contains(&Edge(u, v)) || edges.contains(&Edge(v, u)) {
+                continue;
+            }
+            if rng.gen_bool(cycle_probability) && !edge_intersects(params, Edge(u, v), edges, nodes)
+            {
+                edges.insert(Edge(u, v));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+fn cycles_added(probability: f64) -> (HashSet<Edge>, HashSet<Edge>, Vec<Node>, Params) {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 200.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(6);
+    let tree = gen_maze_prim(&mut rng, &params, &nodes, nodes[0]);
+    let mut edges = tree.clone();
+    add_cycles(&mut rng, &params, &mut edges, &nodes, probability);
+    (tree, edges, nodes, params)
+}
+
+#[test]
+fn test_add_cycles_scales_with_probability() {
+    let counts: Vec<usize> = [0.0, 0.05, 0.1, 0.2]
+        .into_iter()
+        .map(|p| {
+            let (tree, edges, _, _) = cycles_added(p);
+            edges.len() - tree.len()
+        })
+        .collect();
+    assert_eq!(counts[0], 0);
+    for pair in counts[1..].windows(2) {
+        let ratio = pair[1] as f64 / pair[0] as f64;
+        assert!((1.4..2.6).contains(&ratio), "{counts:?}");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+
+// Distance between the centers of adjacent cells.
+pub const GRID_CELL_SIZE: f64 = 10.0;
+
+// A classic maze on a `width_cells` by `height_cells` grid of cells, carved by
+// a depth-first search that only moves between orthogonally adjacent cells.
+// Cell (x, y) is node `y * width_cells + x`, centered in a rectangular
+// boundary. It starts at the top left cell and ends at the bottom right one.
+// Both sizes must be at least 1.
+pub fn gen_maze_rectangular_grid_perfect(
+    width_cells: usize,
+    height_cells: usize,
+    rng: &mut impl Rng,
+) -> Maze {
+    assert!(width_cells > 0 && height_cells > 0);
+    let (width, height) = (
+        width_cells as f64 * GRID_CELL_SIZE,
+        height_cells as f64 * GRID_CELL_SIZE,
+    );
+    let nodes: Vec<Node> = (0..width_cells * height_cells)
+        .map(|index| Node {
+            point: V2 {
+                x: ((index % width_cells) as f64 + 0.5) * GRID_CELL_SIZE - width / 2.0,
+                y: ((index / width_cells) as f64 + 0.5) * GRID_CELL_SIZE - height / 2.0,
+            },
+            index,
+        })
+        .collect();
+    let adjacent = |i: Index| {
+        let (x, y) = (i % width_cells, i / width_cells);
+        let mut cells = Vec::with_capacity(4);
+        if x > 0 {
+            cells.push(i - 1);
+        }
+        if x + 1 < width_cells {
+            cells.push(i + 1);
+        }
+        if y > 0 {
+            cells.push(i - width_cells);
+        }
+        if y + 1 < height_cells {
+            cells.push(i + width_cells);
+        }
+        cells
+    };
+
+    let mut edges: HashSet<Edge> = HashSet::new();
+    let mut depths: HashMap<Edge, usize> = HashMap::new();
+    let mut visited = vec![false; nodes.len()];
+    visited[0] = true;
+    let mut stack: Vec<Index> = vec![0];
+    while let Some(&current) = stack.last() {
+        let unvisited: Vec<Index> = adjacent(current)
+            .into_iter()
+            .filter(|&i| !visited[i])
+            .collect();
+        let Some(&next) = unvisited.choose(rng) else {
+            stack.pop();
+            continue;
+        };
+        visited[next] = true;
+        edges.insert(Edge(current, next));
+        depths.insert(Edge(current, next), stack.len() - 1);
+        stack.push(next);
+    }
+
+    let tube_radius = GRID_CELL_SIZE * 0.2;
+    let params = Params {
+        radius: width.max(height) / 2.0,
+        tube_radius,
+        min_spacing: GRID_CELL_SIZE,
+        boundary: Some(BoundaryShape::Rectangle { width, height }),
+        ..Default::default()
+    };
+    Maze {
+        nodes,
+        edges,
+        start: 0,
+        end: width_cells * height_cells - 1,
+        metadata: MazeMeta {
+            radius: params.radius,
+            tube_radius,
+            seed: None,
+            algorithm: None,
+            layout: None,
+            min_spacing: Some(params.min_spacing),
+            node_count: width_cells * height_cells,
+        },
+        params,
+        depths,
+    }
+}
+
+#[test]
+fn test_grid_cells_perfect() {
+    use rand::SeedableRng;
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(4);
+    let maze = gen_maze_rectangular_grid_perfect(7, 5, &mut rng);
+    assert_eq!(maze.nodes.len(), 35);
+    assert_eq!((maze.start, maze.end), (0, 34));
+    assert_eq!(verify_spanning_tree(&maze.edges, 35), Ok(()));
+    assert_eq!(verify_maze(&maze), Ok(()));
+    // Every passage joins two cells side by side.
+    for &Edge(a, b) in &maze.edges {
+        let length = (maze.nodes[a].point - maze.nodes[b].point).length();
+        assert!((length - GRID_CELL_SIZE).abs() < 1e-9);
+    }
+    let single = gen_maze_rectangular_grid_perfect(1, 1, &mut rng);
+    assert_eq!((single.nodes.len(), single.edges.len()), (1, 0));
+}
@@ -0,0 +1,160 @@
+use crate::traverse::{dfs_accepts, DfsFrame};
+use crate::*;
+use rand::Rng;
+use std::collections::{HashSet, VecDeque};
+use std::str::FromStr;
+
+// Which active node the growing tree algorithm extends next.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum GrowthStrategy {
+    // The most recently added node; behaves like dfs.
+    #[default]
+    Newest,
+    // The least recently added node; behaves like a breadth-first search.
+    Oldest,
+    Random,
+    MixNewestRandom {
+        newest_prob: f64,
+    },
+}
+
+impl GrowthStrategy {
+    fn select(&self, rng: &mut impl Rng, len: usize) -> usize {
+        match *self {
+            GrowthStrategy::Newest => len - 1,
+            GrowthStrategy::Oldest => 0,
+            GrowthStrategy::Random => rng.gen_range(0..len),
+            GrowthStrategy::MixNewestRandom { newest_prob } => {
+                if rng.gen_bool(newest_prob) {
+                    len - 1
+                } else {
+                    rng.gen_range(0..len)
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for GrowthStrategy {
+    type Err = String;
+
+    // Parses `newest`, `oldest`, `random` or `mix:<newest_prob>`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(GrowthStrategy::Newest),
+            "oldest" => Ok(GrowthStrategy::Oldest),
+            "random" => Ok(GrowthStrategy::Random),
+            _ => {
+                let newest_prob = s
+                    .strip_prefix("mix:")
+                    .and_then(|p| p.parse::<f64>().ok())
+                    .filter(|p| (0.0..=1.0).contains(p))
+                    .ok_or_else(|| {
+                        format!("expected newest, oldest, random or mix:<0.0..1.0>, got {s:?}")
+                    })?;
+                Ok(GrowthStrategy::MixNewestRandom { newest_prob })
+            }
+        }
+    }
+}
+
+// Growing tree: keep a list of active nodes, extend the maze from the one the
+// strategy picks, and retire nodes once all their neighbors have been tried.
+// Uses the same neighbor order and acceptance rules as `dfs`, entering `start`
+// from the left as `MazeBuilder` does.
+pub fn gen_maze_growing_tree(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+    strategy: GrowthStrategy,
+) -> HashSet<Edge> {
+    let cache = NeighborCache::for_params(nodes, params);
+    let prior = start.point - V2 { x: 10.0, y: 0.0 };
+    let mut visited: HashSet<Index> = [start.index].into();
+    let mut edges: HashSet<Edge> = Default::default();
+    let mut midpoints: Vec<V2> = Vec::new();
+    let mut degrees = vec![0; nodes.len()];
+    let mut active: VecDeque<DfsFrame> = VecDeque::new();
+    active.push_back(DfsFrame::new(rng, params, &cache, prior, start, 0));
+    while !active.is_empty() {
+        let i = strategy.select(rng, active.len());
+        let frame = &mut active[i];
+        let Some(node) = frame.next_neighbor() else {
+            active.remove(i);
+            continue;
+        };
+        let DfsFrame {
+            prior,
+            current,
+            depth,
+            ..
+        } = *frame;
+        if visited.contains(&node.index)
+            || !degree_allows(params, &degrees, current.index, node.index)
+            || !dfs_accepts(params, prior, current, node, &edges, nodes, &midpoints)
+        {
+            continue;
+        }
+        degrees[current.index] += 1;
+        degrees[node.index] += 1;
+        midpoints.push((node.point + current.point) * 0.5);
+        visited.insert(node.index);
+        edges.insert(Edge(current.index, node.index));
+        active.push_back(DfsFrame::new(
+            rng,
+            params,
+            &cache,
+            current.point,
+            node,
+            depth + 1,
+        ));
+    }
+    edges
+}
+
+#[test]
+fn test_growing_tree_newest_matches_dfs() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 80.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let cache = NeighborCache::build(&nodes, params.neighbor_k);
+    let start = nodes[nodes.len() / 2];
+
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+    let mut edges: HashSet<Edge> = Default::default();
+    let mut visited: HashSet<Index> = [start.index].into();
+    dfs(
+        &mut rng,
+        &params,
+        start.point - V2 { x: 10.0, y: 0.0 },
+        start,
+        &mut edges,
+        &mut visited,
+        &nodes,
+        &cache,
+        &mut Vec::new(),
+        &mut (0, 0),
+        &mut Default::default(),
+        &mut (),
+    );
+
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+    let grown = gen_maze_growing_tree(&mut rng, &params, &nodes, start, GrowthStrategy::Newest);
+    assert!(edges.len() > 10);
+    assert_eq!(grown, edges);
+}
+
+#[test]
+fn test_growth_strategy_from_str() {
+    assert_eq!("oldest".parse(), Ok(GrowthStrategy::Oldest));
+    assert_eq!(
+        "mix:0.25".parse(),
+        Ok(GrowthStrategy::MixNewestRandom { newest_prob: 0.25 })
+    );
+    assert!("mix:2".parse::<GrowthStrategy>().is_err());
+    assert!("sideways".parse::<GrowthStrategy>().is_err());
+}
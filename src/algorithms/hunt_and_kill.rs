@@ -0,0 +1,79 @@
+use crate::algorithms::{neighbor_lists, Passages};
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Hunt-and-kill: random walk through unvisited neighbors until stuck (kill),
+// then scan the nodes in index order for an unvisited node next to the tree,
+// join it to the tree and walk from there (hunt). Both only take passages
+// that fit.
+pub fn gen_maze_hunt_and_kill(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+) -> HashSet<Edge> {
+    let neighbors = neighbor_lists(nodes, params.neighbor_k);
+    let mut visited = vec![false; nodes.len()];
+    let mut passages = Passages::new(params, nodes);
+    let mut current = Some(start.index);
+    visited[start.index] = true;
+    while let Some(mut u) = current {
+        // Kill.
+        loop {
+            let mut unvisited: Vec<Index> = neighbors[u]
+                .iter()
+                .cloned()
+                .filter(|&v| !visited[v])
+                .collect();
+            unvisited.shuffle(rng);
+            let Some(v) = unvisited.into_iter().find(|&v| passages.carve(Edge(u, v))) else {
+                break;
+            };
+            visited[v] = true;
+            u = v;
+        }
+        // Hunt.
+        current = None;
+        for v in 0..nodes.len() {
+            if visited[v] {
+                continue;
+            }
+            let mut adjacent: Vec<Index> = neighbors[v]
+                .iter()
+                .cloned()
+                .filter(|&w| visited[w])
+                .collect();
+            adjacent.shuffle(rng);
+            if adjacent.into_iter().any(|w| passages.carve(Edge(w, v))) {
+                visited[v] = true;
+                current = Some(v);
+                break;
+            }
+        }
+    }
+    passages.edges
+}
+
+#[test]
+fn test_hunt_and_kill_spanning_tree() {
+    use rand::SeedableRng;
+    // Only the four grid neighbors, so every passage fits.
+    let params = Params {
+        radius: 60.0,
+        neighbor_k: 5,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let edges = gen_maze_hunt_and_kill(&mut rng, &params, &nodes, nodes[0]);
+    assert_eq!(edges.len(), nodes.len() - 1);
+    let touched: HashSet<Index> = edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    assert_eq!(touched.len(), nodes.len());
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    assert_eq!(
+        gen_maze_hunt_and_kill(&mut rng, &params, &nodes, nodes[0]),
+        edges
+    );
+}
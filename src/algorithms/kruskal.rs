@@ -0,0 +1,106 @@
+use crate::algorithms::{neighbor_lists, Passages};
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Disjoint sets over node indices, with path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<Index>,
+    rank: Vec<u8>,
+    count: usize,
+}
+
+impl UnionFind {
+    pub fn new(size: usize) -> Self {
+        Self {
+            parent: (0..size).collect(),
+            rank: vec![0; size],
+            count: size,
+        }
+    }
+
+    pub fn find(&mut self, i: Index) -> Index {
+        let mut root = i;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut i = i;
+        while self.parent[i] != root {
+            let next = self.parent[i];
+            self.parent[i] = root;
+            i = next;
+        }
+        root
+    }
+
+    // Merges the sets holding `a` and `b`, returning false if they were
+    // already the same set.
+    pub fn union(&mut self, a: Index, b: Index) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => self.parent[a] = b,
+            std::cmp::Ordering::Greater => self.parent[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parent[b] = a;
+                self.rank[a] += 1;
+            }
+        }
+        self.count -= 1;
+        true
+    }
+
+    // Number of disjoint sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+// Randomized Kruskal's algorithm: visit the neighbor graph's edges in random
+// order, keeping each one that joins two separate trees and fits. The trees
+// that passages can't join up stay apart.
+pub fn gen_maze_kruskal(rng: &mut impl Rng, params: &Params, nodes: &[Node]) -> HashSet<Edge> {
+    let mut candidates: Vec<Edge> = neighbor_lists(nodes, params.neighbor_k)
+        .iter()
+        .enumerate()
+        .flat_map(|(a, list)| {
+            list.iter()
+                .filter(move |&&b| a < b)
+                .map(move |&b| Edge(a, b))
+        })
+        .collect();
+    candidates.shuffle(rng);
+    let mut sets = UnionFind::new(nodes.len());
+    let mut passages = Passages::new(params, nodes);
+    for edge @ Edge(a, b) in candidates {
+        if sets.find(a) != sets.find(b) && passages.carve(edge) {
+            sets.union(a, b);
+        }
+    }
+    passages.edges
+}
+
+#[test]
+fn test_kruskal_no_cycles() {
+    use rand::SeedableRng;
+    // Only the four grid neighbors, so every passage fits.
+    let params = Params {
+        radius: 60.0,
+        neighbor_k: 5,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let edges = gen_maze_kruskal(&mut rng, &params, &nodes);
+    let mut sets = UnionFind::new(nodes.len());
+    for &Edge(a, b) in &edges {
+        let before = sets.count();
+        assert!(sets.union(a, b), "edge {a}-{b} closes a cycle");
+        assert_eq!(sets.count(), before - 1);
+    }
+    assert_eq!(sets.count(), 1);
+}
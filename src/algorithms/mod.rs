@@ -0,0 +1,159 @@
+use crate::*;
+use std::collections::HashSet;
+
+mod aldous_broder;
+mod braid;
+mod cycles;
+mod grid_cells;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod prim;
+mod recursive_division;
+mod symmetric;
+mod wilson;
+
+pub use aldous_broder::*;
+pub use braid::*;
+pub use cycles::*;
+pub use grid_cells::*;
+pub use growing_tree::*;
+pub use hunt_and_kill::*;
+pub use kruskal::*;
+pub use prim::*;
+pub use recursive_division::*;
+pub use symmetric::*;
+pub use wilson::*;
+
+// Undirected neighbor lists linking every node to its k nearest neighbors, as
+// found by `get_nearest_k`, and to every node that counts it among theirs.
+// Links that would run straight through another node, as on a grid, are left
+// out; that node is always nearer, so it is among the k.
+pub fn neighbor_lists(nodes: &[Node], k: usize) -> Vec<Vec<Index>> {
+    let tree = NodeTree::new(nodes);
+    let mut lists: Vec<Vec<Index>> = vec![Vec::new(); nodes.len()];
+    for &node in nodes {
+        let nearest = tree.nearest_k(node, k);
+        for &neighbor in &nearest {
+            let blocked = nearest.iter().any(|other| {
+                other.index != node.index
+                    && other.index != neighbor.index
+                    && seg::point_on_segment(
+                        other.point,
+                        node.point,
+                        neighbor.point,
+                        seg::ON_SEGMENT,
+                    )
+            });
+            if neighbor.index != node.index && !blocked {
+                lists[node.index].push(neighbor.index);
+                lists[neighbor.index].push(node.index);
+            }
+        }
+    }
+    for list in lists.iter_mut() {
+        list.sort();
+        list.dedup();
+    }
+    lists
+}
+
+// The passages a generator has carved so far. `fits` keeps the rule `dfs`
+// does, that a passage's tube touches no other passage except where they meet
+// at a node. Passages are never removed, so one that doesn't fit never will,
+// and the answer is remembered.
+pub struct Passages<'a> {
+    params: &'a Params,
+    nodes: &'a [Node],
+    pub edges: HashSet<Edge>,
+    blocked: HashSet<(Index, Index)>,
+}
+
+impl<'a> Passages<'a> {
+    pub fn new(params: &'a Params, nodes: &'a [Node]) -> Self {
+        Self {
+            params,
+            nodes,
+            edges: HashSet::new(),
+            blocked: HashSet::new(),
+        }
+    }
+
+    pub fn fits(&mut self, Edge(a, b): Edge) -> bool {
+        let key = (a.min(b), a.max(b));
+        if self.blocked.contains(&key) {
+            return false;
+        }
+        if edge_intersects(self.params, Edge(a, b), &self.edges, self.nodes) {
+            self.blocked.insert(key);
+            return false;
+        }
+        true
+    }
+
+    // Adds `edge` if it fits, returning whether it did.
+    pub fn carve(&mut self, edge: Edge) -> bool {
+        self.fits(edge) && self.edges.insert(edge)
+    }
+}
+
+// The passages of `edges` that can be reached from `start`. Generators that
+// grow several trees at once can leave some apart when the passages that
+// would join them don't fit.
+pub fn tree_containing(edges: HashSet<Edge>, node_count: usize, start: Index) -> HashSet<Edge> {
+    let hops = hop_counts(&edges, node_count, start);
+    edges
+        .into_iter()
+        .filter(|&Edge(a, _)| hops[a].is_some())
+        .collect()
+}
+
+// Labels each node with the lowest index in its connected component.
+pub fn components(neighbors: &[Vec<Index>]) -> Vec<Index> {
+    let mut component: Vec<Option<Index>> = vec![None; neighbors.len()];
+    for root in 0..neighbors.len() {
+        if component[root].is_some() {
+            continue;
+        }
+        component[root] = Some(root);
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            for &v in &neighbors[u] {
+                if component[v].is_none() {
+                    component[v] = Some(root);
+                    stack.push(v);
+                }
+            }
+        }
+    }
+    component.into_iter().map(Option::unwrap).collect()
+}
+
+#[test]
+fn test_algorithms_cross_nothing() {
+    use clap::ValueEnum;
+    // With and without the loops --cycles adds.
+    for cycles in [0.0, 0.3] {
+        for &algorithm in Algorithm::value_variants() {
+            let builder = MazeBuilder::new()
+                .params(Params {
+                    radius: 60.0,
+                    ..Default::default()
+                })
+                .layout(NodeLayout::Halton)
+                .target_nodes(80)
+                .algorithm(algorithm)
+                .cycles(cycles)
+                .seed(1);
+            let maze = builder.build(&mut builder.rng()).unwrap();
+            for &edge in &maze.edges {
+                let others: HashSet<Edge> =
+                    maze.edges.iter().cloned().filter(|&e| e != edge).collect();
+                assert!(
+                    !edge_intersects(&maze.params, edge, &others, &maze.nodes),
+                    "{algorithm:?} with cycles {cycles}: {edge:?} crosses another passage"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::algorithms::{neighbor_lists, Passages};
+use crate::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Randomized Prim's algorithm: keep a frontier of edges leading out of the
+// tree and repeatedly grow the tree along a randomly chosen one that fits.
+pub fn gen_maze_prim(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+) -> HashSet<Edge> {
+    let neighbors = neighbor_lists(nodes, params.neighbor_k);
+    let mut visited = vec![false; nodes.len()];
+    let mut frontier: Vec<Edge> = Vec::new();
+    let mut passages = Passages::new(params, nodes);
+    visited[start.index] = true;
+    frontier.extend(neighbors[start.index].iter().map(|&b| Edge(start.index, b)));
+    while !frontier.is_empty() {
+        let Edge(a, b) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        if visited[b] || !passages.carve(Edge(a, b)) {
+            continue;
+        }
+        visited[b] = true;
+        frontier.extend(
+            neighbors[b]
+                .iter()
+                .filter(|&&c| !visited[c])
+                .map(|&c| Edge(b, c)),
+        );
+    }
+    passages.edges
+}
+
+#[test]
+fn test_prim_spanning_tree() {
+    use rand::SeedableRng;
+    // Only the four grid neighbors, so every passage fits.
+    let params = Params {
+        radius: 60.0,
+        neighbor_k: 5,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let edges = gen_maze_prim(&mut rng, &params, &nodes, nodes[0]);
+    assert_eq!(edges.len(), nodes.len() - 1);
+    let touched: HashSet<Index> = edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    assert_eq!(touched.len(), nodes.len());
+}
@@ -0,0 +1,117 @@
+use crate::algorithms::{neighbor_lists, Passages};
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use std::f64::consts::PI;
+
+// Neighbors per node in the graph that gets divided.
+pub const RECURSIVE_DIVISION_K: usize = 8;
+
+// Splits `region` by a line at a random angle through its centroid, or
+// through its median if every node falls on one side.
+fn split(rng: &mut impl Rng, nodes: &[Node], region: &[Index]) -> (Vec<Index>, Vec<Index>) {
+    let normal: V2 = Pol {
+        a: rng.gen_range(0.0..PI),
+        r: 1.0,
+    }
+    .into();
+    let offset = |i: Index| V2::dot(nodes[i].point, normal);
+    let centroid = region.iter().map(|&i| offset(i)).sum::<f64>() / region.len() as f64;
+    let (a, b): (Vec<Index>, Vec<Index>) = region.iter().partition(|&&i| offset(i) < centroid);
+    if !a.is_empty() && !b.is_empty() {
+        return (a, b);
+    }
+    let mut sorted = region.to_vec();
+    sorted.sort_by(|&a, &b| offset(a).total_cmp(&offset(b)));
+    let b = sorted.split_off(sorted.len() / 2);
+    (sorted, b)
+}
+
+// Recursive division: wall each region off into two halves with a random
+// line, leave a single passage through the wall, and divide each half again
+// until fewer than 3 nodes remain. Halves that the neighbor graph doesn't
+// join up on their own are then linked by random neighbor edges, as in
+// Kruskal's algorithm. Every passage has to fit, so parts that can't be
+// linked stay apart.
+pub fn gen_maze_recursive_division(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    neighbor_k: usize,
+) -> HashSet<Edge> {
+    let neighbors = neighbor_lists(nodes, neighbor_k);
+    let mut passages = Passages::new(params, nodes);
+    let mut sets = UnionFind::new(nodes.len());
+    // Which region each node is in; each split gives one half a new label.
+    let mut label: Vec<usize> = vec![0; nodes.len()];
+    let mut next_label = 1;
+    let mut regions: Vec<Vec<Index>> = vec![(0..nodes.len()).collect()];
+    while let Some(region) = regions.pop() {
+        if region.len() < 3 {
+            if let [a, b] = region[..] {
+                if neighbors[a].contains(&b)
+                    && sets.find(a) != sets.find(b)
+                    && passages.carve(Edge(a, b))
+                {
+                    sets.union(a, b);
+                }
+            }
+            continue;
+        }
+        let (a, b) = split(rng, nodes, &region);
+        for &i in &b {
+            label[i] = next_label;
+        }
+        let mut crossing: Vec<Edge> = a
+            .iter()
+            .flat_map(|&u| {
+                neighbors[u]
+                    .iter()
+                    .filter(|&&v| label[v] == next_label)
+                    .map(move |&v| Edge(u, v))
+            })
+            .collect();
+        next_label += 1;
+        crossing.shuffle(rng);
+        if let Some(Edge(u, v)) = crossing
+            .into_iter()
+            .find(|&passage @ Edge(u, v)| sets.find(u) != sets.find(v) && passages.carve(passage))
+        {
+            sets.union(u, v);
+        }
+        regions.push(a);
+        regions.push(b);
+    }
+
+    let mut leftovers: Vec<Edge> = neighbors
+        .iter()
+        .enumerate()
+        .flat_map(|(a, list)| {
+            list.iter()
+                .filter(move |&&b| a < b)
+                .map(move |&b| Edge(a, b))
+        })
+        .collect();
+    leftovers.shuffle(rng);
+    for edge @ Edge(a, b) in leftovers {
+        if sets.find(a) != sets.find(b) && passages.carve(edge) {
+            sets.union(a, b);
+        }
+    }
+    passages.edges
+}
+
+#[test]
+fn test_recursive_division_is_spanning_tree() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(3);
+    // Only the four grid neighbors, so every passage fits.
+    let edges = gen_maze_recursive_division(&mut rng, &params, &nodes, 5);
+    assert_eq!(verify_spanning_tree(&edges, nodes.len()), Ok(()));
+}
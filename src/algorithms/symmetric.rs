@@ -0,0 +1,93 @@
+use crate::*;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Runs dfs over the first of `symmetry` sectors laid out by
+// `symmetrize_nodes`, then copies its edges into every other sector. One
+// bridge from the first sector into the second is copied the same way, so the
+// sectors join up into a single loop around the center.
+pub fn gen_maze_symmetric(
+    rng: &mut impl Rng,
+    params: &Params,
+    nodes: &[Node],
+    start: Node,
+    symmetry: usize,
+) -> HashSet<Edge> {
+    let n = nodes.len();
+    let m = n / symmetry;
+    let sector = &nodes[..m];
+    let start = sector[start.index % m];
+    let mut sector_edges: HashSet<Edge> = Default::default();
+    let mut visited: HashSet<Index> = [start.index].into();
+    let mut midpoints: Vec<V2> = Vec::new();
+    let mut max_depth_index = (0, 0);
+    dfs(
+        rng,
+        params,
+        start.point - V2 { x: 10.0, y: 0.0 },
+        start,
+        &mut sector_edges,
+        &mut visited,
+        sector,
+        &NeighborCache::for_params(sector, params),
+        &mut midpoints,
+        &mut max_depth_index,
+        &mut Default::default(),
+        &mut (),
+    );
+
+    let copies =
+        |Edge(a, b): Edge| (0..symmetry).map(move |k| Edge((a + k * m) % n, (b + k * m) % n));
+    let mut edges: HashSet<Edge> = sector_edges.iter().flat_map(|&e| copies(e)).collect();
+    if symmetry < 2 {
+        return edges;
+    }
+
+    // The shortest bridge whose copies stay clear of the rest of the maze.
+    let tree = NodeTree::new(nodes);
+    let mut bridges: Vec<Edge> = sector
+        .iter()
+        .flat_map(|&a| {
+            tree.nearest_k(a, params.neighbor_k)
+                .into_iter()
+                .filter(|b| (m..2 * m).contains(&b.index))
+                .map(move |b| Edge(a.index, b.index))
+        })
+        .collect();
+    bridges.sort_by(|&Edge(a, b), &Edge(c, d)| {
+        let ab = (nodes[a].point - nodes[b].point).length();
+        let cd = (nodes[c].point - nodes[d].point).length();
+        ab.total_cmp(&cd)
+    });
+    match bridges
+        .into_iter()
+        .find(|&bridge| copies(bridge).all(|e| !edge_intersects(params, e, &edges, nodes)))
+    {
+        Some(bridge) => edges.extend(copies(bridge)),
+        None => log::warn!("warning: no room to join the sectors of the symmetric maze"),
+    }
+    edges
+}
+
+#[test]
+fn test_symmetric_maze_is_symmetric() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = symmetrize_nodes(&params, &gen_nodes_grid(&params), 4);
+    let n = nodes.len();
+    let m = n / 4;
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(2);
+    let edges = gen_maze_symmetric(&mut rng, &params, &nodes, nodes[0], 4);
+    let rotated: HashSet<Edge> = edges
+        .iter()
+        .map(|&Edge(a, b)| Edge((a + m) % n, (b + m) % n))
+        .collect();
+    assert_eq!(rotated, edges);
+    // Every sector is joined to the next.
+    assert!(edges
+        .iter()
+        .any(|&Edge(a, b)| a < m && (m..2 * m).contains(&b)));
+}
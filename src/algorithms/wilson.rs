@@ -0,0 +1,72 @@
+use crate::algorithms::{components, neighbor_lists, Passages};
+use crate::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+
+// Wilson's algorithm: grow a tree by repeatedly walking at random from a node
+// outside of it until the walk hits the tree, then adding the loop-erased walk.
+// Each connected component of the neighbor graph is rooted at its lowest index.
+// The walk is carved from the tree end back and stops at the first passage
+// that doesn't fit, leaving the rest for a later pass.
+pub fn gen_maze_wilson(rng: &mut impl Rng, params: &Params, nodes: &[Node]) -> HashSet<Edge> {
+    let neighbors = neighbor_lists(nodes, params.neighbor_k);
+    let component = components(&neighbors);
+    let mut in_tree: Vec<bool> = (0..nodes.len()).map(|i| component[i] == i).collect();
+    // Where the walk last left each node; overwriting it on a revisit is what
+    // erases the loop.
+    let mut next: Vec<Index> = vec![0; nodes.len()];
+    let mut passages = Passages::new(params, nodes);
+    let mut walk: Vec<Index> = Vec::new();
+    let mut roots: Vec<Index> = (0..nodes.len()).collect();
+    // Passes go on for as long as they grow the tree.
+    let mut grew = true;
+    while grew {
+        grew = false;
+        roots.retain(|&i| !in_tree[i]);
+        for &i in &roots {
+            let mut u = i;
+            while !in_tree[u] {
+                next[u] = *neighbors[u].choose(rng).unwrap();
+                u = next[u];
+            }
+            walk.clear();
+            let mut u = i;
+            while !in_tree[u] {
+                walk.push(u);
+                u = next[u];
+            }
+            for &u in walk.iter().rev() {
+                if !passages.carve(Edge(u, next[u])) {
+                    break;
+                }
+                in_tree[u] = true;
+                grew = true;
+            }
+        }
+    }
+    passages.edges
+}
+
+#[test]
+fn test_wilson_spanning_tree() {
+    use rand::SeedableRng;
+    // Only the four grid neighbors, none of whose passages cross, so nothing
+    // stops the tree reaching every node.
+    let params = Params {
+        radius: 60.0,
+        neighbor_k: 5,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(1);
+    let edges = gen_maze_wilson(&mut rng, &params, &nodes);
+    assert_eq!(edges.len(), nodes.len() - 1);
+    let mut lists: Vec<Vec<Index>> = vec![Vec::new(); nodes.len()];
+    for &Edge(a, b) in &edges {
+        lists[a].push(b);
+        lists[b].push(a);
+    }
+    // n - 1 edges connecting all n nodes form a tree.
+    assert!(components(&lists).iter().all(|&c| c == 0));
+}
@@ -0,0 +1,229 @@
+use crate::*;
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::f64::consts::TAU;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MazeAnalysis {
+    // Nodes with exactly one passage.
+    pub dead_end_count: usize,
+    // Zero when the end can't be reached from the start.
+    pub solution_hops: usize,
+    pub solution_length_physical: f64,
+    // Mean degree of the nodes with more than one passage.
+    pub average_branching_factor: f64,
+    // How far the deepest branch reaches away from the solution path.
+    pub longest_dead_end_depth: usize,
+}
+
+pub fn analyze_maze(
+    edges: &HashSet<Edge>,
+    nodes: &[Node],
+    start: Index,
+    end: Index,
+) -> MazeAnalysis {
+    let graph = MazeGraph::from_edges(edges, nodes.len());
+    let dead_end_count = graph.dead_ends().len();
+    let branching: Vec<usize> = (0..nodes.len())
+        .map(|i| graph.degree(i))
+        .filter(|&degree| degree > 1)
+        .collect();
+    let average_branching_factor = if branching.is_empty() {
+        0.0
+    } else {
+        branching.iter().sum::<usize>() as f64 / branching.len() as f64
+    };
+
+    let solution = solve_bfs(edges, nodes, start, end);
+    let (solution_hops, solution_length_physical) = match &solution {
+        Some(path) => (path.len() - 1, path_length(nodes, path)),
+        None => (0, 0.0),
+    };
+
+    // Breadth-first out from every node on the solution path at once.
+    let sources = solution.unwrap_or_else(|| vec![start]);
+    let mut depth: Vec<Option<usize>> = vec![None; nodes.len()];
+    for &s in &sources {
+        depth[s] = Some(0);
+    }
+    let mut queue: VecDeque<Index> = sources.into();
+    let mut longest_dead_end_depth = 0;
+    while let Some(u) = queue.pop_front() {
+        let d = depth[u].unwrap();
+        longest_dead_end_depth = longest_dead_end_depth.max(d);
+        for &v in graph.neighbors(u) {
+            if depth[v].is_none() {
+                depth[v] = Some(d + 1);
+                queue.push_back(v);
+            }
+        }
+    }
+
+    MazeAnalysis {
+        dead_end_count,
+        solution_hops,
+        solution_length_physical,
+        average_branching_factor,
+        longest_dead_end_depth,
+    }
+}
+
+// A summary of one generated maze, for filtering batches of them by quality.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MazeReport {
+    pub seed: u64,
+    pub algorithm: String,
+    pub layout: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+    pub dead_end_count: usize,
+    // Nodes with three or more passages.
+    pub junction_count: usize,
+    // None when the end can't be reached from the start.
+    pub solution_hops: Option<usize>,
+    pub solution_length: Option<f64>,
+    // The most hops between any two connected nodes.
+    pub diameter: usize,
+    pub avg_degree: f64,
+    pub generation_time_ms: u64,
+    pub svg_bytes: usize,
+}
+
+// The name clap gives `value` on the command line.
+pub(crate) fn value_name(value: &impl clap::ValueEnum) -> String {
+    value.to_possible_value().unwrap().get_name().to_string()
+}
+
+pub fn maze_report(maze: &Maze, generation_time: Duration, svg_bytes: usize) -> MazeReport {
+    let graph = MazeGraph::from_edges(&maze.edges, maze.nodes.len());
+    let degrees: Vec<usize> = (0..maze.nodes.len()).map(|i| graph.degree(i)).collect();
+    let solution = solve_bfs(&maze.edges, &maze.nodes, maze.start, maze.end);
+    MazeReport {
+        seed: maze.metadata.seed.unwrap_or_default(),
+        algorithm: maze
+            .metadata
+            .algorithm
+            .map_or("none".to_string(), |a| value_name(&a)),
+        layout: maze
+            .metadata
+            .layout
+            .map_or("none".to_string(), |l| value_name(&l)),
+        node_count: maze.nodes.len(),
+        edge_count: maze.edges.len(),
+        dead_end_count: degrees.iter().filter(|&&d| d == 1).count(),
+        junction_count: degrees.iter().filter(|&&d| d >= 3).count(),
+        solution_hops: solution.as_ref().map(|path| path.len() - 1),
+        solution_length: solution.as_ref().map(|path| path_length(&maze.nodes, path)),
+        // Braiding and --cycles add loops, where the double search in
+        // `compute_diameter` can come up short.
+        diameter: if graph.is_tree() {
+            compute_diameter(&graph).0
+        } else {
+            graph.diameter()
+        },
+        avg_degree: if maze.nodes.is_empty() {
+            0.0
+        } else {
+            2.0 * maze.edges.len() as f64 / maze.nodes.len() as f64
+        },
+        generation_time_ms: generation_time.as_millis() as u64,
+        svg_bytes,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectorStats {
+    pub sector_id: usize,
+    pub node_count: usize,
+    // Edges whose midpoint lies in the sector.
+    pub edge_count: usize,
+    // Those of `edge_count` whose ends lie in different sectors.
+    pub crossing_edge_count: usize,
+    pub avg_edge_length: f64,
+}
+
+// Which of `num_sectors` equal wedges around the center `point` lies in,
+// counting counterclockwise from the positive x axis.
+fn sector_of(point: V2, num_sectors: usize) -> usize {
+    let angle = point.y.atan2(point.x).rem_euclid(TAU);
+    ((angle / TAU * num_sectors as f64) as usize).min(num_sectors - 1)
+}
+
+pub fn partition_by_sector(
+    nodes: &[Node],
+    edges: &HashSet<Edge>,
+    num_sectors: usize,
+) -> Vec<SectorStats> {
+    let mut stats: Vec<SectorStats> = (0..num_sectors)
+        .map(|sector_id| SectorStats {
+            sector_id,
+            node_count: 0,
+            edge_count: 0,
+            crossing_edge_count: 0,
+            avg_edge_length: 0.0,
+        })
+        .collect();
+    for node in nodes {
+        stats[sector_of(node.point, num_sectors)].node_count += 1;
+    }
+    for &Edge(a, b) in edges {
+        let (a, b) = (nodes[a].point, nodes[b].point);
+        let sector = &mut stats[sector_of((a + b) * 0.5, num_sectors)];
+        sector.edge_count += 1;
+        if sector_of(a, num_sectors) != sector_of(b, num_sectors) {
+            sector.crossing_edge_count += 1;
+        }
+        // Summed here and divided below.
+        sector.avg_edge_length += (a - b).length();
+    }
+    for sector in &mut stats {
+        if sector.edge_count > 0 {
+            sector.avg_edge_length /= sector.edge_count as f64;
+        }
+    }
+    stats
+}
+
+#[test]
+fn test_analyze_maze() {
+    let nodes = line_nodes(8);
+    // The solution runs 0-1-2-3, with a branch 1-4-5-6 and a spur 2-7.
+    let edges: HashSet<Edge> = [
+        Edge(0, 1),
+        Edge(1, 2),
+        Edge(2, 3),
+        Edge(1, 4),
+        Edge(4, 5),
+        Edge(5, 6),
+        Edge(2, 7),
+    ]
+    .into();
+    let analysis = analyze_maze(&edges, &nodes, 0, 3);
+    assert_eq!(analysis.dead_end_count, 4);
+    assert_eq!(analysis.solution_hops, 3);
+    assert_eq!(analysis.solution_length_physical, 3.0);
+    // Nodes 1 and 2 have degree 3, nodes 4 and 5 degree 2.
+    assert_eq!(analysis.average_branching_factor, 2.5);
+    assert_eq!(analysis.longest_dead_end_depth, 3);
+}
+
+#[test]
+fn test_partition_by_sector() {
+    let builder = MazeBuilder::new()
+        .params(Params {
+            radius: 60.0,
+            ..Default::default()
+        })
+        .seed(5);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let stats = partition_by_sector(&maze.nodes, &maze.edges, 6);
+    assert_eq!(stats.len(), 6);
+    let total = |f: fn(&SectorStats) -> usize| stats.iter().map(f).sum::<usize>();
+    assert_eq!(total(|s| s.node_count), maze.nodes.len());
+    assert_eq!(total(|s| s.edge_count), maze.edges.len());
+    assert!(total(|s| s.crossing_edge_count) > 0);
+    assert!(stats
+        .iter()
+        .all(|s| s.crossing_edge_count <= s.edge_count && s.avg_edge_length > 0.0));
+}
@@ -0,0 +1,171 @@
+use crate::*;
+
+// The outline nodes are kept inside. Rectangles are centered on the origin.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundaryShape {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Polygon { vertices: Vec<V2> },
+}
+
+impl BoundaryShape {
+    // Opposite corners of the bounding box.
+    pub fn bounds(&self) -> (V2, V2) {
+        match self {
+            Self::Circle { radius } => (
+                V2 {
+                    x: -radius,
+                    y: -radius,
+                },
+                V2 {
+                    x: *radius,
+                    y: *radius,
+                },
+            ),
+            Self::Rectangle { width, height } => (
+                V2 {
+                    x: -width / 2.0,
+                    y: -height / 2.0,
+                },
+                V2 {
+                    x: width / 2.0,
+                    y: height / 2.0,
+                },
+            ),
+            Self::Polygon { vertices } => vertices.iter().fold(
+                (
+                    V2 {
+                        x: f64::INFINITY,
+                        y: f64::INFINITY,
+                    },
+                    V2 {
+                        x: f64::NEG_INFINITY,
+                        y: f64::NEG_INFINITY,
+                    },
+                ),
+                |(lo, hi), v| {
+                    (
+                        V2 {
+                            x: lo.x.min(v.x),
+                            y: lo.y.min(v.y),
+                        },
+                        V2 {
+                            x: hi.x.max(v.x),
+                            y: hi.y.max(v.y),
+                        },
+                    )
+                },
+            ),
+        }
+    }
+
+    pub fn area(&self) -> f64 {
+        match self {
+            Self::Circle { radius } => std::f64::consts::PI * radius * radius,
+            Self::Rectangle { width, height } => width * height,
+            // The shoelace formula.
+            Self::Polygon { vertices } => {
+                polygon_edges(vertices)
+                    .map(|(a, b)| a.x * b.y - b.x * a.y)
+                    .sum::<f64>()
+                    .abs()
+                    / 2.0
+            }
+        }
+    }
+
+    // Whether `point` is inside and at least `margin` away from the outline.
+    pub fn contains(&self, point: V2, margin: f64) -> bool {
+        match self {
+            Self::Circle { radius } => point.length() <= radius - margin,
+            Self::Rectangle { width, height } => {
+                point.x.abs() <= width / 2.0 - margin && point.y.abs() <= height / 2.0 - margin
+            }
+            Self::Polygon { vertices } => {
+                inside_boundary(point, self)
+                    && polygon_edges(vertices).all(|(a, b)| segment_distance(point, a, b) >= margin)
+            }
+        }
+    }
+}
+
+// Even-odd ray casting for polygons.
+pub fn inside_boundary(point: V2, shape: &BoundaryShape) -> bool {
+    match shape {
+        BoundaryShape::Polygon { vertices } => {
+            let mut inside = false;
+            for (a, b) in polygon_edges(vertices) {
+                if (a.y > point.y) != (b.y > point.y)
+                    && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+                {
+                    inside = !inside;
+                }
+            }
+            inside
+        }
+        _ => shape.contains(point, 0.0),
+    }
+}
+
+fn polygon_edges(vertices: &[V2]) -> impl Iterator<Item = (V2, V2)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+fn segment_distance(point: V2, a: V2, b: V2) -> f64 {
+    let ab = b - a;
+    let t = if ab.length_squared() == 0.0 {
+        0.0
+    } else {
+        (V2::dot(point - a, ab) / ab.length_squared()).clamp(0.0, 1.0)
+    };
+    (a + ab * t - point).length()
+}
+
+#[test]
+fn test_area() {
+    let point = |x, y| V2 { x, y };
+    assert_eq!(
+        BoundaryShape::Rectangle {
+            width: 3.0,
+            height: 4.0
+        }
+        .area(),
+        12.0
+    );
+    assert_eq!(
+        BoundaryShape::Circle { radius: 2.0 }.area(),
+        4.0 * std::f64::consts::PI
+    );
+    // A right triangle, listed clockwise.
+    let triangle = BoundaryShape::Polygon {
+        vertices: vec![point(0.0, 0.0), point(0.0, 3.0), point(4.0, 0.0)],
+    };
+    assert_eq!(triangle.area(), 6.0);
+}
+
+#[test]
+fn test_inside_boundary() {
+    let point = |x, y| V2 { x, y };
+    // An L shape.
+    let shape = BoundaryShape::Polygon {
+        vertices: vec![
+            point(0.0, 0.0),
+            point(10.0, 0.0),
+            point(10.0, 4.0),
+            point(4.0, 4.0),
+            point(4.0, 10.0),
+            point(0.0, 10.0),
+        ],
+    };
+    assert!(inside_boundary(point(2.0, 2.0), &shape));
+    assert!(inside_boundary(point(8.0, 2.0), &shape));
+    assert!(inside_boundary(point(2.0, 8.0), &shape));
+    assert!(!inside_boundary(point(8.0, 8.0), &shape));
+    assert!(!inside_boundary(point(-1.0, 2.0), &shape));
+    assert!(shape.contains(point(2.0, 2.0), 1.5));
+    assert!(!shape.contains(point(8.0, 3.0), 1.5));
+    assert_eq!(shape.bounds(), (point(0.0, 0.0), point(10.0, 10.0)));
+}
@@ -0,0 +1,94 @@
+use hex_color::HexColor;
+use std::f64::consts::TAU;
+
+// What decides the color of each passage.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorBy {
+    // Every passage gets the path color.
+    #[default]
+    Solid,
+    // Passages shade from the start color to the end color the deeper into
+    // the traversal they were added.
+    Depth,
+    // Passages run through `heatmap_color` by how many hops they are from
+    // the start.
+    Distance,
+    // Passages take the hue of the direction they point in.
+    Angle,
+}
+
+// Linear interpolation of each channel, with `t` clamped to [0, 1].
+pub fn lerp_color(from: HexColor, to: HexColor, t: f64) -> HexColor {
+    let t = t.clamp(0.0, 1.0);
+    let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    HexColor::rgba(
+        lerp(from.r, to.r),
+        lerp(from.g, to.g),
+        lerp(from.b, to.b),
+        lerp(from.a, to.a),
+    )
+}
+
+// Blue, cyan, green, yellow then red as `t` goes from 0 to 1.
+pub fn heatmap_color(t: f64) -> HexColor {
+    const STOPS: [HexColor; 5] = [
+        HexColor::rgb(0, 0, 255),
+        HexColor::rgb(0, 255, 255),
+        HexColor::rgb(0, 255, 0),
+        HexColor::rgb(255, 255, 0),
+        HexColor::rgb(255, 0, 0),
+    ];
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+    let i = (scaled as usize).min(STOPS.len() - 2);
+    lerp_color(STOPS[i], STOPS[i + 1], scaled - i as f64)
+}
+
+// `h` in degrees, `s` and `l` in [0, 1].
+pub fn hsl_to_rgb(h: f64, s: f64, l: f64) -> HexColor {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+    HexColor::rgb(channel(r), channel(g), channel(b))
+}
+
+// A fully saturated hue for a direction in radians, red along +x.
+pub fn angle_to_color(angle: f64) -> HexColor {
+    hsl_to_rgb(angle.rem_euclid(TAU) * 360.0 / TAU, 1.0, 0.5)
+}
+
+#[test]
+fn test_angle_to_color() {
+    assert_eq!(angle_to_color(0.0), HexColor::rgb(255, 0, 0));
+    assert_eq!(angle_to_color(TAU / 3.0), HexColor::rgb(0, 255, 0));
+    assert_eq!(angle_to_color(-TAU / 3.0), HexColor::rgb(0, 0, 255));
+    assert_eq!(angle_to_color(TAU / 4.0), HexColor::rgb(128, 255, 0));
+    assert_eq!(hsl_to_rgb(0.0, 0.0, 0.5), HexColor::rgb(128, 128, 128));
+}
+
+#[test]
+fn test_heatmap_color() {
+    assert_eq!(heatmap_color(0.0), HexColor::rgb(0, 0, 255));
+    assert_eq!(heatmap_color(0.25), HexColor::rgb(0, 255, 255));
+    assert_eq!(heatmap_color(0.625), HexColor::rgb(128, 255, 0));
+    assert_eq!(heatmap_color(1.0), HexColor::rgb(255, 0, 0));
+}
+
+#[test]
+fn test_lerp_color() {
+    let from = HexColor::rgb(0x00, 0x33, 0x66);
+    let to = HexColor::rgb(0xFF, 0xFF, 0x00);
+    assert_eq!(lerp_color(from, to, 0.0), from);
+    assert_eq!(lerp_color(from, to, 1.0), to);
+    assert_eq!(lerp_color(from, to, 0.5), HexColor::rgb(0x80, 0x99, 0x33));
+    assert_eq!(lerp_color(from, to, 2.0), to);
+}
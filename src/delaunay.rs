@@ -0,0 +1,136 @@
+use crate::*;
+use delaunator::{triangulate, Point};
+use std::collections::HashMap;
+
+// Neighbors along the edges of the Delaunay triangulation of `nodes`, which
+// never cross each other. Nodes that all lie on one line are linked in order
+// along it.
+pub fn delaunay_neighbors(nodes: &[Node]) -> HashMap<Index, Vec<Index>> {
+    let points: Vec<Point> = nodes
+        .iter()
+        .map(|n| Point {
+            x: n.point.x,
+            y: n.point.y,
+        })
+        .collect();
+    let triangulation = triangulate(&points);
+    let mut neighbors: HashMap<Index, Vec<Index>> =
+        nodes.iter().map(|n| (n.index, Vec::new())).collect();
+    let mut link = |a: usize, b: usize| {
+        neighbors
+            .get_mut(&nodes[a].index)
+            .unwrap()
+            .push(nodes[b].index);
+        neighbors
+            .get_mut(&nodes[b].index)
+            .unwrap()
+            .push(nodes[a].index);
+    };
+    if triangulation.is_empty() {
+        for pair in triangulation.hull.windows(2) {
+            link(pair[0], pair[1]);
+        }
+    } else {
+        // Each edge between two triangles shows up as a pair of half-edges;
+        // take the one going from the lower index, or the only one on the hull.
+        for (e, &opposite) in triangulation.halfedges.iter().enumerate() {
+            if opposite == delaunator::EMPTY || e < opposite {
+                let a = triangulation.triangles[e];
+                let b = triangulation.triangles[delaunator::next_halfedge(e)];
+                link(a, b);
+            }
+        }
+    }
+    for list in neighbors.values_mut() {
+        list.sort();
+        list.dedup();
+    }
+    neighbors
+}
+
+// The Delaunay links `(u, v)` with no other node strictly inside the circle
+// whose diameter is `(u, v)`. Such a node would be nearer the midpoint than
+// `u` and `v`, so only the three nodes nearest the midpoint need checking.
+pub fn gabriel_neighbors(nodes: &[Node]) -> HashMap<Index, Vec<Index>> {
+    let by_index: HashMap<Index, V2> = nodes.iter().map(|n| (n.index, n.point)).collect();
+    let tree = NodeTree::new(nodes);
+    let mut neighbors = delaunay_neighbors(nodes);
+    for (&u, list) in neighbors.iter_mut() {
+        list.retain(|&v| {
+            let (a, b) = (by_index[&u], by_index[&v]);
+            let midpoint = Node {
+                point: (a + b) * 0.5,
+                index: Index::MAX,
+            };
+            let radius = (a - b).length() / 2.0;
+            tree.nearest_k(midpoint, 3).iter().all(|w| {
+                w.index == u || w.index == v || (w.point - midpoint.point).length() >= radius
+            })
+        });
+    }
+    neighbors
+}
+
+#[test]
+fn test_delaunay_neighbors_square() {
+    // A square around its center: each corner links to its two sides and the
+    // center, but not to the opposite corner.
+    let nodes: Vec<Node> = [
+        (0.0, 0.0),
+        (-1.0, -1.0),
+        (1.0, -1.0),
+        (1.0, 1.0),
+        (-1.0, 1.0),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(index, &(x, y))| Node {
+        point: V2 { x, y },
+        index,
+    })
+    .collect();
+    let neighbors = delaunay_neighbors(&nodes);
+    assert_eq!(neighbors[&0], vec![1, 2, 3, 4]);
+    assert_eq!(neighbors[&1], vec![0, 2, 4]);
+    assert_eq!(neighbors[&3], vec![0, 2, 4]);
+}
+
+#[test]
+fn test_delaunay_neighbors_collinear() {
+    let nodes: Vec<Node> = [2.0, 0.0, 3.0, 1.0]
+        .iter()
+        .enumerate()
+        .map(|(index, &x)| Node {
+            point: V2 { x, y: 0.0 },
+            index,
+        })
+        .collect();
+    let neighbors = delaunay_neighbors(&nodes);
+    assert_eq!(neighbors[&1], vec![3]);
+    assert_eq!(neighbors[&3], vec![0, 1]);
+    assert_eq!(neighbors[&0], vec![2, 3]);
+}
+
+#[test]
+fn test_gabriel_neighbors() {
+    // A wide rectangle around its center, which sits inside the circles on
+    // the long sides but not the short ones.
+    let nodes: Vec<Node> = [
+        (0.0, 0.0),
+        (-1.0, -0.5),
+        (1.0, -0.5),
+        (1.0, 0.5),
+        (-1.0, 0.5),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(index, &(x, y))| Node {
+        point: V2 { x, y },
+        index,
+    })
+    .collect();
+    let neighbors = gabriel_neighbors(&nodes);
+    assert_eq!(neighbors[&0], vec![1, 2, 3, 4]);
+    assert_eq!(neighbors[&1], vec![0, 4]);
+    assert_eq!(neighbors[&3], vec![0, 2]);
+}
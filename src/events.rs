@@ -0,0 +1,332 @@
+use crate::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::io::{BufWriter, Stdout, Write};
+use std::time::Duration;
+
+// Receives the steps of maze generation as they happen. Every method does
+// nothing by default, and `()` ignores everything.
+pub trait MazeObserver {
+    // There are `count` nodes placed so far, and `done` is set once placement
+    // stops. Only the random layout reports this, as it can run for a long
+    // time.
+    fn on_nodes_placed(&mut self, _count: usize, _done: bool) {}
+    // A node was placed, before any passages are carved.
+    fn on_node_added(&mut self, _node: &Node) {}
+    // The maze reached a node, `depth` steps into the search.
+    fn on_node_visited(&mut self, _node: &Node, _depth: usize) {}
+    fn on_edge_added(&mut self, _edge: Edge, _nodes: &[Node]) {}
+    fn on_complete(&mut self, _maze: &Maze) {}
+    // A phase of generation, such as placing nodes, took `elapsed`.
+    fn on_phase_done(&mut self, _phase: &str, _elapsed: Duration) {}
+}
+
+impl MazeObserver for () {}
+
+// So an observer can be lent to a `Vec` of them and read back afterwards.
+impl<T: MazeObserver + ?Sized> MazeObserver for &mut T {
+    fn on_nodes_placed(&mut self, count: usize, done: bool) {
+        (**self).on_nodes_placed(count, done);
+    }
+
+    fn on_node_added(&mut self, node: &Node) {
+        (**self).on_node_added(node);
+    }
+
+    fn on_node_visited(&mut self, node: &Node, depth: usize) {
+        (**self).on_node_visited(node, depth);
+    }
+
+    fn on_edge_added(&mut self, edge: Edge, nodes: &[Node]) {
+        (**self).on_edge_added(edge, nodes);
+    }
+
+    fn on_complete(&mut self, maze: &Maze) {
+        (**self).on_complete(maze);
+    }
+
+    fn on_phase_done(&mut self, phase: &str, elapsed: Duration) {
+        (**self).on_phase_done(phase, elapsed);
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullObserver;
+
+impl MazeObserver for NullObserver {}
+
+// Passes every step on to each observer in turn.
+impl MazeObserver for Vec<Box<dyn MazeObserver + '_>> {
+    fn on_nodes_placed(&mut self, count: usize, done: bool) {
+        self.iter_mut().for_each(|o| o.on_nodes_placed(count, done));
+    }
+
+    fn on_node_added(&mut self, node: &Node) {
+        self.iter_mut().for_each(|o| o.on_node_added(node));
+    }
+
+    fn on_node_visited(&mut self, node: &Node, depth: usize) {
+        self.iter_mut().for_each(|o| o.on_node_visited(node, depth));
+    }
+
+    fn on_edge_added(&mut self, edge: Edge, nodes: &[Node]) {
+        self.iter_mut().for_each(|o| o.on_edge_added(edge, nodes));
+    }
+
+    fn on_complete(&mut self, maze: &Maze) {
+        self.iter_mut().for_each(|o| o.on_complete(maze));
+    }
+
+    fn on_phase_done(&mut self, phase: &str, elapsed: Duration) {
+        self.iter_mut()
+            .for_each(|o| o.on_phase_done(phase, elapsed));
+    }
+}
+
+// Shows a spinner with the node count while nodes are placed, then a bar
+// with the share of nodes visited, on stderr when it is a terminal.
+#[derive(Debug, Default)]
+pub struct ProgressObserver {
+    nodes: usize,
+    spinner: Option<ProgressBar>,
+    bar: Option<ProgressBar>,
+}
+
+impl ProgressObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl MazeObserver for ProgressObserver {
+    fn on_nodes_placed(&mut self, count: usize, done: bool) {
+        let spinner = self.spinner.get_or_insert_with(ProgressBar::new_spinner);
+        spinner.set_message(format!("placed {count} nodes"));
+        if done {
+            spinner.finish_and_clear();
+        }
+    }
+
+    fn on_node_added(&mut self, _node: &Node) {
+        self.nodes += 1;
+    }
+
+    fn on_node_visited(&mut self, _node: &Node, _depth: usize) {
+        let nodes = self.nodes as u64;
+        self.bar
+            .get_or_insert_with(|| {
+                ProgressBar::new(nodes).with_style(
+                    ProgressStyle::with_template("visiting nodes {wide_bar} {percent}%").unwrap(),
+                )
+            })
+            .inc(1);
+    }
+
+    fn on_complete(&mut self, _maze: &Maze) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+    }
+}
+
+// How long each phase of making a maze took, in order: those `MazeBuilder`
+// reports, plus any recorded by the caller, such as saving files.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PhaseTiming {
+    pub phases: Vec<(String, Duration)>,
+}
+
+impl PhaseTiming {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, phase: impl Into<String>, elapsed: Duration) {
+        self.phases.push((phase.into(), elapsed));
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    // A row per phase with its milliseconds and share of the total.
+    pub fn table(&self) -> String {
+        let width = self
+            .phases
+            .iter()
+            .map(|(phase, _)| phase.len())
+            .chain(["Phase".len()])
+            .max()
+            .unwrap_or_default()
+            + 3;
+        let total = self.total().as_secs_f64();
+        let mut table = format!("{:<width$}{:<11}Fraction\n", "Phase", "Time(ms)");
+        for (phase, elapsed) in &self.phases {
+            let fraction = if total > 0.0 {
+                elapsed.as_secs_f64() / total
+            } else {
+                0.0
+            };
+            table += &format!(
+                "{phase:<width$}{:<11}{:.1}%\n",
+                elapsed.as_millis(),
+                fraction * 100.0
+            );
+        }
+        table
+    }
+}
+
+impl MazeObserver for PhaseTiming {
+    fn on_phase_done(&mut self, phase: &str, elapsed: Duration) {
+        self.record(phase, elapsed);
+    }
+}
+
+pub fn print_timing_table(timing: &PhaseTiming) {
+    eprint!("{}", timing.table());
+}
+
+// How much is logged to stderr.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    // The seed, node and edge counts, and warnings.
+    #[default]
+    Normal,
+    // Every node and passage as well, through `LogObserver`.
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn level_filter(self) -> log::LevelFilter {
+        match self {
+            Verbosity::Quiet => log::LevelFilter::Off,
+            Verbosity::Normal => log::LevelFilter::Info,
+            Verbosity::Verbose => log::LevelFilter::Debug,
+        }
+    }
+}
+
+// Logs each node and passage at debug level as it is added.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogObserver;
+
+impl MazeObserver for LogObserver {
+    fn on_node_added(&mut self, node: &Node) {
+        log::debug!(
+            "node {} at ({:.2}, {:.2})",
+            node.index,
+            node.point.x,
+            node.point.y
+        );
+    }
+
+    fn on_edge_added(&mut self, Edge(a, b): Edge, _nodes: &[Node]) {
+        log::debug!("edge {a}-{b}");
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum MazeEvent {
+    NodeAdded {
+        index: Index,
+        x: f64,
+        y: f64,
+    },
+    EdgeAdded {
+        a: Index,
+        b: Index,
+    },
+    Complete {
+        nodes: usize,
+        edges: usize,
+        start: Index,
+        end: Index,
+    },
+}
+
+// Writes each event to stdout as a line of JSON.
+pub struct JsonStreamSink {
+    writer: BufWriter<Stdout>,
+}
+
+impl JsonStreamSink {
+    pub fn new() -> Self {
+        Self {
+            writer: BufWriter::new(std::io::stdout()),
+        }
+    }
+
+    fn emit(&mut self, event: MazeEvent) {
+        // Nothing is left to report to if stdout has gone away.
+        let _ = serde_json::to_writer(&mut self.writer, &event)
+            .map_err(std::io::Error::from)
+            .and_then(|_| self.writer.write_all(b"\n"));
+    }
+}
+
+impl Default for JsonStreamSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MazeObserver for JsonStreamSink {
+    fn on_node_added(&mut self, node: &Node) {
+        self.emit(MazeEvent::NodeAdded {
+            index: node.index,
+            x: node.point.x,
+            y: node.point.y,
+        });
+    }
+
+    fn on_edge_added(&mut self, Edge(a, b): Edge, _nodes: &[Node]) {
+        self.emit(MazeEvent::EdgeAdded { a, b });
+    }
+
+    fn on_complete(&mut self, maze: &Maze) {
+        self.emit(MazeEvent::Complete {
+            nodes: maze.nodes.len(),
+            edges: maze.edges.len(),
+            start: maze.start,
+            end: maze.end,
+        });
+        let _ = self.writer.flush();
+    }
+}
+
+#[test]
+fn test_event_json() {
+    let line = |event| serde_json::to_string(&event).unwrap();
+    assert_eq!(
+        line(MazeEvent::NodeAdded {
+            index: 42,
+            x: 1.5,
+            y: -2.25
+        }),
+        r#"{"event":"node_added","index":42,"x":1.5,"y":-2.25}"#
+    );
+    assert_eq!(
+        line(MazeEvent::EdgeAdded { a: 41, b: 42 }),
+        r#"{"event":"edge_added","a":41,"b":42}"#
+    );
+}
+
+#[test]
+fn test_timing_table() {
+    let mut timing = PhaseTiming::new();
+    timing.record("Node generation", Duration::from_millis(1824));
+    timing.record("DFS traversal", Duration::from_millis(142));
+    timing.record("SVG construction", Duration::from_millis(30));
+    timing.record("File I/O", Duration::from_millis(4));
+    assert_eq!(
+        timing.table(),
+        "Phase              Time(ms)   Fraction\n\
+         Node generation    1824       91.2%\n\
+         DFS traversal      142        7.1%\n\
+         SVG construction   30         1.5%\n\
+         File I/O           4          0.2%\n"
+    );
+}
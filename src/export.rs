@@ -0,0 +1,715 @@
+use crate::*;
+use hex_color::HexColor;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use svg::Document;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MazeMeta {
+    pub radius: f64,
+    pub tube_radius: f64,
+    // How the maze was made, where known; older files leave these out.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub algorithm: Option<Algorithm>,
+    #[serde(default)]
+    pub layout: Option<NodeLayout>,
+    #[serde(default)]
+    pub min_spacing: Option<f64>,
+    #[serde(default)]
+    pub node_count: usize,
+}
+
+// How a maze is written out; the depths and the remaining params aren't
+// stored.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct MazeJson {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    start: Index,
+    end: Index,
+    #[serde(flatten)]
+    meta: MazeMeta,
+    // Left out for a full disk, as in older files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    boundary: Option<BoundaryRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inner_radius: Option<f64>,
+}
+
+// How a boundary is written out, since `V2` has no serde support.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub(crate) enum BoundaryRecord {
+    Circle { radius: f64 },
+    Rectangle { width: f64, height: f64 },
+    Polygon { vertices: Vec<[f64; 2]> },
+}
+
+impl From<BoundaryRecord> for BoundaryShape {
+    fn from(r: BoundaryRecord) -> Self {
+        match r {
+            BoundaryRecord::Circle { radius } => Self::Circle { radius },
+            BoundaryRecord::Rectangle { width, height } => Self::Rectangle { width, height },
+            BoundaryRecord::Polygon { vertices } => Self::Polygon {
+                vertices: vertices.into_iter().map(|[x, y]| V2 { x, y }).collect(),
+            },
+        }
+    }
+}
+
+impl From<BoundaryShape> for BoundaryRecord {
+    fn from(b: BoundaryShape) -> Self {
+        match b {
+            BoundaryShape::Circle { radius } => Self::Circle { radius },
+            BoundaryShape::Rectangle { width, height } => Self::Rectangle { width, height },
+            BoundaryShape::Polygon { vertices } => Self::Polygon {
+                vertices: vertices.into_iter().map(|v| [v.x, v.y]).collect(),
+            },
+        }
+    }
+}
+
+impl From<Maze> for MazeJson {
+    fn from(maze: Maze) -> Self {
+        Self {
+            edges: maze.sorted_edges(),
+            nodes: maze.nodes,
+            start: maze.start,
+            end: maze.end,
+            meta: maze.metadata,
+            boundary: maze.params.boundary.map(BoundaryRecord::from),
+            inner_radius: Some(maze.params.inner_radius).filter(|&r| r > 0.0),
+        }
+    }
+}
+
+// Parameters not stored in the JSON take their defaults.
+impl TryFrom<MazeJson> for Maze {
+    type Error = MazeError;
+
+    fn try_from(json: MazeJson) -> Result<Self> {
+        let node_count = json.nodes.len();
+        if let Some((i, _)) = json.nodes.iter().enumerate().find(|(i, n)| n.index != *i) {
+            return Err(MazeError::ParseError {
+                field: "nodes".to_string(),
+                msg: format!("node {i} is out of order"),
+            });
+        }
+        for &index in [json.start, json.end]
+            .iter()
+            .chain(json.edges.iter().flat_map(|e| [&e.0, &e.1]))
+        {
+            if index >= node_count {
+                return Err(MazeError::ParseError {
+                    field: "edges".to_string(),
+                    msg: format!("node index {index} out of range"),
+                });
+            }
+        }
+        let boundary = json.boundary.map(BoundaryShape::from);
+        if let Some(boundary) = &boundary {
+            check_boundary(boundary)?;
+        }
+        let edges: HashSet<Edge> = json.edges.into_iter().collect();
+        let defaults = Params::default();
+        Ok(Maze {
+            nodes: json.nodes,
+            depths: edge_depths(&edges, node_count, json.start, Default::default()),
+            edges,
+            start: json.start,
+            end: json.end,
+            metadata: MazeMeta {
+                node_count,
+                ..json.meta
+            },
+            params: Params {
+                radius: json.meta.radius,
+                tube_radius: json.meta.tube_radius,
+                min_spacing: json.meta.min_spacing.unwrap_or(defaults.min_spacing),
+                inner_radius: json.inner_radius.unwrap_or_default(),
+                boundary,
+                ..defaults
+            },
+        })
+    }
+}
+
+// A boundary read from a file has to enclose something, or drawing it panics.
+fn check_boundary(boundary: &BoundaryShape) -> Result<()> {
+    let positive = |size: f64| size.is_finite() && size > 0.0;
+    let msg = match boundary {
+        BoundaryShape::Circle { radius } if !positive(*radius) => {
+            format!("circle radius {radius} is not positive")
+        }
+        BoundaryShape::Rectangle { width, height } if !(positive(*width) && positive(*height)) => {
+            format!("rectangle {width}x{height} is empty")
+        }
+        BoundaryShape::Polygon { vertices } if vertices.len() < 3 => {
+            format!("polygon has {} vertices, not at least 3", vertices.len())
+        }
+        BoundaryShape::Polygon { vertices }
+            if !vertices.iter().all(|v| v.x.is_finite() && v.y.is_finite()) =>
+        {
+            "polygon vertices must be finite".to_string()
+        }
+        _ => return Ok(()),
+    };
+    Err(MazeError::ParseError {
+        field: "boundary".to_string(),
+        msg,
+    })
+}
+
+// Fills in `{seed}`, `{timestamp}` (seconds since the epoch), `{nodes}` and
+// `{algorithm}` in an output file name. Unknown seeds and algorithms become
+// "none".
+pub fn expand_output_template(pattern: &str, meta: &MazeMeta) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let seed = meta.seed.map_or("none".to_string(), |s| s.to_string());
+    let algorithm = meta.algorithm.map_or("none".to_string(), |a| {
+        clap::ValueEnum::to_possible_value(&a)
+            .unwrap()
+            .get_name()
+            .to_string()
+    });
+    PathBuf::from(
+        pattern
+            .replace("{seed}", &seed)
+            .replace("{timestamp}", &timestamp.to_string())
+            .replace("{nodes}", &meta.node_count.to_string())
+            .replace("{algorithm}", &algorithm),
+    )
+}
+
+pub fn export_json(maze: &Maze) -> serde_json::Value {
+    serde_json::to_value(maze).unwrap()
+}
+
+pub fn import_json(value: &serde_json::Value) -> Result<Maze> {
+    Ok(Maze::deserialize(value)?)
+}
+
+pub fn save_maze(maze: &Maze, path: &Path) -> Result<()> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    serde_json::to_writer(file, maze)?;
+    Ok(())
+}
+
+pub fn load_maze(path: &Path) -> Result<Maze> {
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    Ok(serde_json::from_reader(file)?)
+}
+
+// Rasterizes an SVG document to PNG at `dpi`, taking its user units as CSS
+// pixels at 96 dpi.
+pub fn export_png(svg: &str, dpi: f64) -> Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default())
+        .map_err(|e| MazeError::Render(format!("can't parse the SVG: {e}")))?;
+    let scale = (dpi / 96.0) as f32;
+    let size = tree.size();
+    let width = (size.width() * scale).round() as u32;
+    let height = (size.height() * scale).round() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| MazeError::Render(format!("can't make a {width}x{height} image")))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    pixmap
+        .encode_png()
+        .map_err(|e| MazeError::Render(e.to_string()))
+}
+
+// Gzipped SVG at the default compression level.
+pub fn save_svgz(document: &Document, path: &Path) -> Result<()> {
+    save_svgz_with_level(document, path, flate2::Compression::default().level())
+}
+
+// `level` runs from 0 (store only) to 9 (smallest).
+pub fn save_svgz_with_level(document: &Document, path: &Path, level: u32) -> Result<()> {
+    let file = std::fs::File::create(path)?;
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::new(level));
+    svg::write(&mut encoder, document)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    Svg,
+    Svgz,
+    Png,
+    Dot,
+    Json,
+    // The text from `render_ascii`, saved as .txt.
+    Ascii,
+    // The lines from `export_edge_list`.
+    #[value(name = "edgelist")]
+    EdgeList,
+    // A TikZ picture from `export_tikz`, saved as .tex.
+    Tikz,
+    // Every format above.
+    All,
+}
+
+impl OutputFormat {
+    pub const EACH: [OutputFormat; 8] = [
+        OutputFormat::Svg,
+        OutputFormat::Svgz,
+        OutputFormat::Png,
+        OutputFormat::Dot,
+        OutputFormat::Json,
+        OutputFormat::Ascii,
+        OutputFormat::EdgeList,
+        OutputFormat::Tikz,
+    ];
+
+    // Empty for `All`, which writes a file per format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "svg",
+            OutputFormat::Svgz => "svgz",
+            OutputFormat::Png => "png",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Json => "json",
+            OutputFormat::Ascii => "txt",
+            OutputFormat::EdgeList => "edgelist",
+            OutputFormat::Tikz => "tex",
+            OutputFormat::All => "",
+        }
+    }
+
+    // The formats to write, with `All` spelled out and repeats dropped.
+    pub fn expand(formats: &[OutputFormat]) -> Vec<OutputFormat> {
+        let mut each: Vec<OutputFormat> = Vec::new();
+        for &format in formats {
+            let formats = match format {
+                OutputFormat::All => &Self::EACH[..],
+                _ => std::slice::from_ref(&format),
+            };
+            for &format in formats {
+                if !each.contains(&format) {
+                    each.push(format);
+                }
+            }
+        }
+        each
+    }
+}
+
+// Width of the picture `write_output` saves for `OutputFormat::Tikz`, about
+// the width of the text on a page.
+pub const TIKZ_WIDTH_CM: f64 = 15.0;
+
+// Saves `maze` as `base_path` with the extension of `format`, or once per
+// format for `All`. PNGs are 96 dpi, svgz uses the default compression level,
+// text is 80 by 40 and TikZ is `TIKZ_WIDTH_CM` wide in the default theme.
+pub fn write_output(
+    maze: &Maze,
+    document: &Document,
+    format: OutputFormat,
+    base_path: &Path,
+) -> Result<()> {
+    write_output_with_options(maze, document, &RenderOptions::default(), format, base_path)
+}
+
+// Like `write_output`, taking the colors for TikZ from `opts`.
+pub fn write_output_with_options(
+    maze: &Maze,
+    document: &Document,
+    opts: &RenderOptions,
+    format: OutputFormat,
+    base_path: &Path,
+) -> Result<()> {
+    let path = base_path.with_extension(format.extension());
+    match format {
+        OutputFormat::Svg => svg::save(&path, document)?,
+        OutputFormat::Svgz => save_svgz(document, &path)?,
+        OutputFormat::Png => std::fs::write(&path, export_png(&document.to_string(), 96.0)?)?,
+        OutputFormat::Dot => {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            export_dot(maze, &mut writer)?;
+            writer.flush()?;
+        }
+        OutputFormat::Json => save_maze(maze, &path)?,
+        OutputFormat::Ascii => std::fs::write(&path, render_ascii(maze, 80, 40))?,
+        OutputFormat::EdgeList => {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            export_edge_list(maze, &mut writer)?;
+            writer.flush()?;
+        }
+        OutputFormat::Tikz => {
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+            export_tikz_with_options(maze, &mut writer, TIKZ_WIDTH_CM, opts)?;
+            writer.flush()?;
+        }
+        OutputFormat::All => {
+            for format in OutputFormat::EACH {
+                write_output_with_options(maze, document, opts, format, base_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Positions are pinned with `!`, so `neato` keeps the maze layout.
+// y is flipped because DOT's y axis points up and SVG's points down.
+pub fn export_dot(maze: &Maze, writer: &mut impl Write) -> Result<()> {
+    writeln!(writer, "graph maze {{")?;
+    for node in &maze.nodes {
+        let color = if node.index == maze.start {
+            ", color=green"
+        } else if node.index == maze.end {
+            ", color=red"
+        } else {
+            ""
+        };
+        writeln!(
+            writer,
+            "  {0} [label=\"{0}\", pos=\"{1},{2}!\"{3}];",
+            node.index, node.point.x, -node.point.y, color
+        )?;
+    }
+    for Edge(a, b) in maze.sorted_edges() {
+        writeln!(writer, "  {a} -- {b};")?;
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+// One `a b x_a y_a x_b y_b` line per passage, which NetworkX's
+// `read_edgelist` and graph-tool read as an edge list with extra columns.
+// Everything else goes in `#` comments, which they skip: a header of
+// `key=value` pairs, then the position of any node with no passages.
+pub fn export_edge_list(maze: &Maze, writer: &mut impl Write) -> Result<()> {
+    writeln!(
+        writer,
+        "# nodes={} edges={} radius={} tube_radius={} start={} end={}",
+        maze.nodes.len(),
+        maze.edges.len(),
+        maze.params.radius,
+        maze.params.tube_radius,
+        maze.start,
+        maze.end
+    )?;
+    let mut linked = vec![false; maze.nodes.len()];
+    for &Edge(a, b) in &maze.edges {
+        linked[a] = true;
+        linked[b] = true;
+    }
+    for node in maze.nodes.iter().filter(|n| !linked[n.index]) {
+        writeln!(
+            writer,
+            "# node {} {} {}",
+            node.index, node.point.x, node.point.y
+        )?;
+    }
+    for Edge(a, b) in maze.sorted_edges() {
+        let (pa, pb) = (maze.nodes[a].point, maze.nodes[b].point);
+        writeln!(writer, "{a} {b} {} {} {} {}", pa.x, pa.y, pb.x, pb.y)?;
+    }
+    Ok(())
+}
+
+// A CSS color from a theme as the six hex digits `\definecolor{..}{HTML}`
+// takes.
+fn tikz_color(color: &str) -> Result<String> {
+    let hex = match color {
+        "white" => "#FFFFFF",
+        "black" => "#000000",
+        "green" => "#008000",
+        "red" => "#FF0000",
+        color => color,
+    };
+    let c = HexColor::parse_rgb(hex).map_err(|e| MazeError::InvalidParams {
+        field: "color".to_string(),
+        msg: format!("{color:?}: {e}"),
+    })?;
+    Ok(format!("{:02X}{:02X}{:02X}", c.r, c.g, c.b))
+}
+
+// A TikZ picture in the default theme; see `export_tikz_with_options`.
+pub fn export_tikz(maze: &Maze, writer: &mut impl Write, scale_cm: f64) -> Result<()> {
+    export_tikz_with_options(maze, writer, scale_cm, &RenderOptions::default())
+}
+
+// The maze as a tikzpicture for `\input` in a LaTeX document that loads
+// tikz, `scale_cm` centimeters across the boundary's bounding box. Passages
+// are solid lines in the colors of `opts`, whatever its other settings.
+// y is flipped because TikZ's y axis points up and SVG's points down.
+pub fn export_tikz_with_options(
+    maze: &Maze,
+    writer: &mut impl Write,
+    scale_cm: f64,
+    opts: &RenderOptions,
+) -> Result<()> {
+    let params = &maze.params;
+    let boundary = params.boundary();
+    let (top_left, bottom_right) = boundary.bounds();
+    let cm = scale_cm / (bottom_right.x - top_left.x);
+    let at = |p: V2| format!("({:.4},{:.4})", p.x * cm, -p.y * cm);
+    writeln!(
+        writer,
+        "% A maze of {} nodes and {} passages, {scale_cm} cm wide.",
+        maze.nodes.len(),
+        maze.edges.len()
+    )?;
+    writeln!(
+        writer,
+        "% Needs \\usepackage{{tikz}}; include it with \\input."
+    )?;
+    writeln!(writer, "\\begin{{tikzpicture}}")?;
+    for (name, color) in [
+        ("mazebackground", &opts.background_color),
+        ("mazeboundary", &opts.boundary_color),
+        ("mazepath", &opts.path_color),
+        ("mazestart", &opts.start_color),
+        ("mazeend", &opts.end_color),
+    ] {
+        writeln!(
+            writer,
+            "\\definecolor{{{name}}}{{HTML}}{{{}}}",
+            tikz_color(color)?
+        )?;
+    }
+    writeln!(
+        writer,
+        "\\fill[mazebackground] {} rectangle {};",
+        at(top_left),
+        at(bottom_right)
+    )?;
+    let outline = match &*boundary {
+        BoundaryShape::Circle { radius } => format!("(0,0) circle ({:.4})", radius * cm),
+        BoundaryShape::Rectangle { width, height } => format!(
+            "{} rectangle {}",
+            at(V2 {
+                x: -width / 2.0,
+                y: -height / 2.0
+            }),
+            at(V2 {
+                x: width / 2.0,
+                y: height / 2.0
+            })
+        ),
+        BoundaryShape::Polygon { vertices } => {
+            let corners: Vec<String> = vertices.iter().map(|&v| at(v)).collect();
+            format!("{} -- cycle", corners.join(" -- "))
+        }
+    };
+    writeln!(writer, "\\fill[mazeboundary] {outline};")?;
+    if params.inner_radius > 0.0 {
+        writeln!(
+            writer,
+            "\\fill[mazebackground] (0,0) circle ({:.4});",
+            params.inner_radius * cm
+        )?;
+    }
+    let node_radius = params.tube_radius * opts.draw_factor * cm;
+    writeln!(
+        writer,
+        "\\begin{{scope}}[mazepath, line width={:.4}cm]",
+        node_radius * 2.0
+    )?;
+    let mut linked = vec![false; maze.nodes.len()];
+    for Edge(a, b) in maze.sorted_edges() {
+        linked[a] = true;
+        linked[b] = true;
+        let (pa, pb) = (maze.nodes[a].point, maze.nodes[b].point);
+        writeln!(writer, "\\draw {} -- {};", at(pa), at(pb))?;
+    }
+    for node in maze.nodes.iter().filter(|n| linked[n.index]) {
+        writeln!(
+            writer,
+            "\\fill {} circle ({node_radius:.4});",
+            at(node.point)
+        )?;
+    }
+    writeln!(writer, "\\end{{scope}}")?;
+    let marker_radius = params.tube_radius * 1.75 * cm;
+    for (name, index) in [("mazestart", maze.start), ("mazeend", maze.end)] {
+        writeln!(
+            writer,
+            "\\fill[{name}] {} circle ({marker_radius:.4});",
+            at(maze.nodes[index].point)
+        )?;
+    }
+    writeln!(writer, "\\end{{tikzpicture}}")?;
+    Ok(())
+}
+
+fn edge_list_error(line: usize, msg: impl fmt::Display) -> MazeError {
+    MazeError::ParseError {
+        field: format!("edge list line {line}"),
+        msg: msg.to_string(),
+    }
+}
+
+// Reads what `export_edge_list` writes. Every node needs a position, from a
+// passage or a `# node` line.
+pub fn import_edge_list(reader: &mut impl BufRead) -> Result<Maze> {
+    let mut header: HashMap<String, String> = HashMap::new();
+    let mut points: HashMap<Index, V2> = HashMap::new();
+    let mut edges: Vec<Edge> = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = i + 1;
+        let parse = |word: &str| {
+            word.parse::<f64>()
+                .map_err(|e| edge_list_error(number, format!("{word:?}: {e}")))
+        };
+        let parse_index = |word: &str| {
+            word.parse::<Index>()
+                .map_err(|e| edge_list_error(number, format!("{word:?}: {e}")))
+        };
+        if let Some(comment) = line.strip_prefix('#') {
+            let words: Vec<&str> = comment.split_whitespace().collect();
+            if let ["node", index, x, y] = words[..] {
+                let point = V2 {
+                    x: parse(x)?,
+                    y: parse(y)?,
+                };
+                points.insert(parse_index(index)?, point);
+            } else {
+                for (key, value) in words.iter().filter_map(|w| w.split_once('=')) {
+                    header.insert(key.to_string(), value.to_string());
+                }
+            }
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [] => {}
+            [a, b, xa, ya, xb, yb] => {
+                let (a, b) = (parse_index(a)?, parse_index(b)?);
+                points.insert(
+                    a,
+                    V2 {
+                        x: parse(xa)?,
+                        y: parse(ya)?,
+                    },
+                );
+                points.insert(
+                    b,
+                    V2 {
+                        x: parse(xb)?,
+                        y: parse(yb)?,
+                    },
+                );
+                edges.push(Edge(a, b));
+            }
+            _ => {
+                return Err(edge_list_error(
+                    number,
+                    format!("expected a b x_a y_a x_b y_b, got {line:?}"),
+                ))
+            }
+        }
+    }
+    let field = |key: &str| -> Result<&str> {
+        header
+            .get(key)
+            .map(String::as_str)
+            .ok_or_else(|| edge_list_error(1, format!("the header has no {key}")))
+    };
+    let number = |key: &str| -> Result<f64> {
+        field(key)?
+            .parse()
+            .map_err(|e| edge_list_error(1, format!("{key}: {e}")))
+    };
+    let index = |key: &str| -> Result<Index> {
+        field(key)?
+            .parse()
+            .map_err(|e| edge_list_error(1, format!("{key}: {e}")))
+    };
+    let node_count = index("nodes")?;
+    if edges.len() != index("edges")? {
+        return Err(edge_list_error(
+            1,
+            format!("expected {} edges, found {}", index("edges")?, edges.len()),
+        ));
+    }
+    let nodes = (0..node_count)
+        .map(|i| {
+            points
+                .get(&i)
+                .map(|&point| Node { point, index: i })
+                .ok_or_else(|| edge_list_error(1, format!("node {i} has no position")))
+        })
+        .collect::<Result<Vec<Node>>>()?;
+    Maze::try_from(MazeJson {
+        nodes,
+        edges,
+        start: index("start")?,
+        end: index("end")?,
+        meta: MazeMeta {
+            radius: number("radius")?,
+            tube_radius: number("tube_radius")?,
+            seed: None,
+            algorithm: None,
+            layout: None,
+            min_spacing: None,
+            node_count,
+        },
+        boundary: None,
+        inner_radius: None,
+    })
+}
+
+#[test]
+fn test_import_json_rejects_bad_index() {
+    let value = serde_json::json!({
+        "nodes": [{"index": 0, "x": 0.0, "y": 0.0}],
+        "edges": [[0, 1]],
+        "start": 0,
+        "end": 0,
+        "radius": 10.0,
+        "tube_radius": 1.0,
+    });
+    assert!(import_json(&value).is_err());
+}
+
+#[test]
+fn test_import_json_rejects_bad_boundary() {
+    let with_boundary = |boundary: &serde_json::Value| {
+        serde_json::json!({
+            "nodes": [{"index": 0, "x": 0.0, "y": 0.0}],
+            "edges": [],
+            "start": 0,
+            "end": 0,
+            "radius": 10.0,
+            "tube_radius": 1.0,
+            "boundary": boundary,
+        })
+    };
+    for boundary in [
+        serde_json::json!({"shape": "polygon", "vertices": []}),
+        serde_json::json!({"shape": "polygon", "vertices": [[0.0, 0.0], [1.0, 0.0]]}),
+        serde_json::json!({"shape": "rectangle", "width": 0.0, "height": 5.0}),
+        serde_json::json!({"shape": "rectangle", "width": 5.0, "height": -1.0}),
+        serde_json::json!({"shape": "circle", "radius": 0.0}),
+    ] {
+        let value = with_boundary(&boundary);
+        assert!(import_json(&value).is_err(), "{boundary} was accepted");
+        assert!(matches!(
+            Maze::try_from(MazeJson::deserialize(&value).unwrap()),
+            Err(MazeError::ParseError { ref field, .. }) if field == "boundary"
+        ));
+    }
+    let triangle = serde_json::json!({
+        "shape": "polygon",
+        "vertices": [[-5.0, -5.0], [5.0, -5.0], [0.0, 5.0]],
+    });
+    let maze = import_json(&with_boundary(&triangle)).unwrap();
+    render_svg(&maze, &RenderOptions::default());
+}
@@ -0,0 +1,195 @@
+use crate::*;
+use std::collections::{HashSet, VecDeque};
+
+// The passages of a maze as an undirected graph.
+#[derive(Debug, Clone)]
+pub struct MazeGraph {
+    adjacency: Vec<Vec<Index>>,
+    edge_set: HashSet<Edge>,
+    node_count: usize,
+}
+
+impl MazeGraph {
+    pub fn from_edges(edges: &HashSet<Edge>, node_count: usize) -> MazeGraph {
+        let mut adjacency: Vec<Vec<Index>> = vec![Vec::new(); node_count];
+        for &Edge(a, b) in edges {
+            adjacency[a].push(b);
+            adjacency[b].push(a);
+        }
+        MazeGraph {
+            adjacency,
+            edge_set: edges.clone(),
+            node_count,
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.node_count
+    }
+
+    pub fn edges(&self) -> &HashSet<Edge> {
+        &self.edge_set
+    }
+
+    pub fn neighbors(&self, idx: Index) -> &[Index] {
+        &self.adjacency[idx]
+    }
+
+    pub fn degree(&self, idx: Index) -> usize {
+        self.adjacency[idx].len()
+    }
+
+    // Breadth-first hop counts from `start`, or None where it can't be reached.
+    pub fn hops_from(&self, start: Index) -> Vec<Option<usize>> {
+        let mut hops: Vec<Option<usize>> = vec![None; self.node_count];
+        hops[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            for &v in &self.adjacency[u] {
+                if hops[v].is_none() {
+                    hops[v] = hops[u].map(|h| h + 1);
+                    queue.push_back(v);
+                }
+            }
+        }
+        hops
+    }
+
+    // Whether every node can be reached from node 0.
+    pub fn is_connected(&self) -> bool {
+        self.node_count == 0 || self.hops_from(0).iter().all(Option::is_some)
+    }
+
+    // Whether the passages form a single tree, leaving out the nodes with none.
+    pub fn is_tree(&self) -> bool {
+        let Some(first) = (0..self.node_count).find(|&i| self.degree(i) > 0) else {
+            return true;
+        };
+        let reached = self.hops_from(first).iter().flatten().count();
+        reached == self.edge_set.len() + 1
+    }
+
+    // Nodes with exactly one passage.
+    pub fn dead_ends(&self) -> Vec<Index> {
+        (0..self.node_count)
+            .filter(|&i| self.degree(i) == 1)
+            .collect()
+    }
+
+    // The most hops on a shortest path between any two nodes that are joined
+    // at all.
+    pub fn diameter(&self) -> usize {
+        (0..self.node_count)
+            .filter_map(|i| self.hops_from(i).into_iter().flatten().max())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+// The diameter of a tree and the two nodes at its ends, found by searching
+// from the first node with a passage to the farthest node `u`, then from `u`
+// to the farthest node `v`. This is only exact when the passages form a tree.
+pub fn compute_diameter(graph: &MazeGraph) -> (usize, Index, Index) {
+    let farthest = |from: Index| {
+        graph
+            .hops_from(from)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, hops)| Some((hops?, i)))
+            .max()
+            .unwrap_or((0, from))
+    };
+    let Some(first) = (0..graph.node_count()).find(|&i| graph.degree(i) > 0) else {
+        return (0, 0, 0);
+    };
+    let (_, u) = farthest(first);
+    let (diameter, v) = farthest(u);
+    (diameter, u, v)
+}
+
+// The nodes and passages of the component with the most nodes; ties go to the
+// one holding the lowest index. A node with no passages is a component of
+// its own.
+pub fn largest_connected_component(
+    edges: &HashSet<Edge>,
+    node_count: usize,
+) -> (HashSet<Index>, HashSet<Edge>) {
+    let mut sets = UnionFind::new(node_count);
+    for &Edge(a, b) in edges {
+        sets.union(a, b);
+    }
+    let mut sizes: Vec<usize> = vec![0; node_count];
+    for i in 0..node_count {
+        sizes[sets.find(i)] += 1;
+    }
+    let Some(root) = (0..node_count).max_by_key(|&i| (sizes[i], std::cmp::Reverse(i))) else {
+        return Default::default();
+    };
+    let nodes: HashSet<Index> = (0..node_count).filter(|&i| sets.find(i) == root).collect();
+    let edges = edges
+        .iter()
+        .copied()
+        .filter(|&Edge(a, _)| nodes.contains(&a))
+        .collect();
+    (nodes, edges)
+}
+
+#[test]
+fn test_maze_graph() {
+    // A path 0-1-2-3 with a spur 1-4, and 5 on its own.
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(1, 2), Edge(2, 3), Edge(4, 1)].into();
+    let graph = MazeGraph::from_edges(&edges, 6);
+    let mut neighbors = graph.neighbors(1).to_vec();
+    neighbors.sort();
+    assert_eq!(neighbors, [0, 2, 4]);
+    assert_eq!(graph.degree(1), 3);
+    assert_eq!(graph.degree(5), 0);
+    assert_eq!(graph.dead_ends(), [0, 3, 4]);
+    assert_eq!(graph.diameter(), 3);
+    assert_eq!(
+        graph.hops_from(4),
+        [Some(2), Some(1), Some(2), Some(3), Some(0), None]
+    );
+    assert!(!graph.is_connected());
+    assert!(MazeGraph::from_edges(&edges, 5).is_connected());
+    assert!(MazeGraph::from_edges(&HashSet::new(), 0).is_connected());
+    assert!(graph.is_tree());
+    let with_loop: HashSet<Edge> = edges.iter().copied().chain([Edge(0, 4)]).collect();
+    assert!(!MazeGraph::from_edges(&with_loop, 6).is_tree());
+    let forest: HashSet<Edge> = edges.iter().copied().chain([Edge(5, 6)]).collect();
+    assert!(!MazeGraph::from_edges(&forest, 7).is_tree());
+    assert!(MazeGraph::from_edges(&HashSet::new(), 3).is_tree());
+}
+
+#[test]
+fn test_largest_connected_component() {
+    // 0-1-2 and 3-4-5-6 with 7 on its own.
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(2, 1), Edge(3, 4), Edge(5, 4), Edge(5, 6)].into();
+    let (nodes, lcc) = largest_connected_component(&edges, 8);
+    assert_eq!(nodes, [3, 4, 5, 6].into());
+    assert_eq!(lcc, [Edge(3, 4), Edge(5, 4), Edge(5, 6)].into());
+    // With no passages each node is alone, and the first wins.
+    let (nodes, lcc) = largest_connected_component(&HashSet::new(), 3);
+    assert_eq!((nodes, lcc), ([0].into(), HashSet::new()));
+    assert_eq!(
+        largest_connected_component(&HashSet::new(), 0),
+        (HashSet::new(), HashSet::new())
+    );
+}
+
+#[test]
+fn test_compute_diameter() {
+    // 0 is bare; the longest route in the tree is 5-4-1-2-3.
+    let edges: HashSet<Edge> = [Edge(1, 2), Edge(2, 3), Edge(1, 4), Edge(4, 5), Edge(6, 2)].into();
+    let graph = MazeGraph::from_edges(&edges, 7);
+    let (diameter, u, v) = compute_diameter(&graph);
+    assert_eq!(diameter, 4);
+    assert_eq!(diameter, graph.diameter());
+    let mut ends = [u, v];
+    ends.sort();
+    assert!(ends == [3, 5] || ends == [5, 6]);
+    assert_eq!(
+        compute_diameter(&MazeGraph::from_edges(&HashSet::new(), 3)),
+        (0, 0, 0)
+    );
+}
@@ -0,0 +1,171 @@
+use crate::*;
+use kd_tree::KdMap;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+// Static k-d tree over node positions, built once after node generation.
+#[derive(Debug, Clone)]
+pub struct NodeTree {
+    nodes: Vec<Node>,
+    tree: KdMap<[f64; 2], usize>,
+}
+
+impl NodeTree {
+    pub fn new(nodes: &[Node]) -> Self {
+        let entries = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, n)| ([n.point.x, n.point.y], i))
+            .collect();
+        Self {
+            nodes: nodes.to_vec(),
+            tree: KdMap::build_by_ordered_float(entries),
+        }
+    }
+
+    // Same result as `get_nearest_k`, including how ties are broken.
+    pub fn nearest_k(&self, cur: Node, k: usize) -> Vec<Node> {
+        let query = [cur.point.x, cur.point.y];
+        let Some(farthest) = self
+            .tree
+            .nearests(&query, k)
+            .last()
+            .map(|n| n.squared_distance)
+        else {
+            return Vec::new();
+        };
+        // Collect everything tied with the k-th nearest node, then order ties by
+        // position like the stable sort in `get_nearest_k` does.
+        let radius = farthest.sqrt() * (1.0 + 1e-9) + 1e-9;
+        let mut found: Vec<(f64, usize)> = self
+            .tree
+            .within_radius(&query, radius)
+            .into_iter()
+            .map(|&(_, i)| ((self.nodes[i].point - cur.point).length_squared(), i))
+            .collect();
+        found.sort_by(|a, b| {
+            a.0.partial_cmp(&b.0)
+                .unwrap_or(Ordering::Equal)
+                .then(a.1.cmp(&b.1))
+        });
+        found.truncate(k);
+        found.into_iter().map(|(_, i)| self.nodes[i]).collect()
+    }
+}
+
+// The nearest neighbors of every node, found once up front so traversals
+// don't query the tree as they go.
+#[derive(Debug, Clone)]
+pub struct NeighborCache {
+    neighbors: HashMap<Index, Vec<Node>>,
+}
+
+impl NeighborCache {
+    pub fn build(nodes: &[Node], k: usize) -> Self {
+        let tree = NodeTree::new(nodes);
+        Self {
+            neighbors: nodes
+                .iter()
+                .map(|&n| (n.index, tree.nearest_k(n, k)))
+                .collect(),
+        }
+    }
+
+    // The given neighbors, such as from `delaunay_neighbors`, leaving out links
+    // whose midpoint falls outside the maze, as across a hole or a concave
+    // boundary.
+    pub fn from_neighbors(
+        nodes: &[Node],
+        params: &Params,
+        neighbors: HashMap<Index, Vec<Index>>,
+    ) -> Self {
+        let by_index: HashMap<Index, Node> = nodes.iter().map(|&n| (n.index, n)).collect();
+        Self {
+            neighbors: neighbors
+                .into_iter()
+                .map(|(index, list)| {
+                    let node = by_index[&index];
+                    let list = list
+                        .into_iter()
+                        .map(|i| by_index[&i])
+                        .filter(|n| params.admits((n.point + node.point) * 0.5))
+                        .collect();
+                    (index, list)
+                })
+                .collect(),
+        }
+    }
+
+    // The neighbors `params.connectivity` asks for, less those too near or
+    // far for `params.min_edge_length` and `params.max_edge_length`.
+    pub fn for_params(nodes: &[Node], params: &Params) -> Self {
+        let mut cache = match params.connectivity {
+            Connectivity::Knn => Self::build(nodes, params.neighbor_k),
+            Connectivity::Delaunay => {
+                Self::from_neighbors(nodes, params, delaunay_neighbors(nodes))
+            }
+            Connectivity::Gabriel => Self::from_neighbors(nodes, params, gabriel_neighbors(nodes)),
+        };
+        let lengths = params.min_edge_length..=params.max_edge_length;
+        let points: HashMap<Index, V2> = nodes.iter().map(|n| (n.index, n.point)).collect();
+        for (index, list) in cache.neighbors.iter_mut() {
+            list.retain(|n| lengths.contains(&(n.point - points[index]).length()));
+        }
+        cache
+    }
+
+    // From `build`, the same nodes in the same order as `NodeTree::nearest_k`.
+    pub fn get(&self, index: Index) -> &[Node] {
+        self.neighbors.get(&index).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[test]
+fn test_nearest_k_matches_sorted_scan() {
+    use rand::{Rng, SeedableRng};
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(3);
+    let random: Vec<Node> = (0..300)
+        .map(|index| Node {
+            point: V2 {
+                x: rng.gen_range(-60.0..60.0),
+                y: rng.gen_range(-60.0..60.0),
+            },
+            index,
+        })
+        .collect();
+    // The grid layout has many equidistant neighbors, which exercises ties.
+    for nodes in [gen_nodes_grid(&params), random] {
+        let tree = NodeTree::new(&nodes);
+        for &node in &nodes {
+            for k in [1, 6, 12, 24] {
+                let expected: Vec<Index> = get_nearest_k(&nodes, node, k)
+                    .iter()
+                    .map(|n| n.index)
+                    .collect();
+                let actual: Vec<Index> = tree.nearest_k(node, k).iter().map(|n| n.index).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_neighbor_cache_matches_tree() {
+    let params = Params {
+        radius: 40.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_grid(&params);
+    let tree = NodeTree::new(&nodes);
+    let cache = NeighborCache::build(&nodes, 6);
+    for &node in &nodes {
+        let expected: Vec<Index> = tree.nearest_k(node, 6).iter().map(|n| n.index).collect();
+        let actual: Vec<Index> = cache.get(node.index).iter().map(|n| n.index).collect();
+        assert_eq!(actual, expected);
+    }
+    assert!(cache.get(nodes.len()).is_empty());
+}
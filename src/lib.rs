@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use vector2d::Vector2D;
+
+mod algorithms;
+mod analysis;
+mod boundary;
+mod color;
+mod delaunay;
+mod events;
+mod export;
+mod graph;
+mod kdtree;
+mod maze;
+mod nodes;
+#[cfg(feature = "petgraph-compat")]
+mod petgraph_compat;
+mod placement;
+mod render;
+pub mod seg;
+mod solve;
+mod spatial;
+mod traverse;
+mod v2ext;
+mod validate;
+mod voronoi;
+
+pub use crate::algorithms::*;
+pub use crate::analysis::*;
+pub use crate::boundary::*;
+pub use crate::color::*;
+pub use crate::delaunay::*;
+pub use crate::events::*;
+pub use crate::export::*;
+pub use crate::graph::*;
+pub use crate::kdtree::*;
+pub use crate::maze::*;
+pub use crate::nodes::*;
+#[cfg(feature = "petgraph-compat")]
+pub use crate::petgraph_compat::*;
+pub use crate::placement::*;
+pub use crate::render::*;
+pub use crate::solve::*;
+pub use crate::spatial::*;
+pub use crate::traverse::*;
+pub use crate::v2ext::*;
+pub use crate::validate::*;
+pub use crate::voronoi::*;
+
+pub type V2 = Vector2D<f64>;
+pub type Result<T> = std::result::Result<T, MazeError>;
+pub type Index = usize;
+
+#[derive(Debug)]
+pub enum MazeError {
+    Io(std::io::Error),
+    SystemTime(std::time::SystemTimeError),
+    Json(serde_json::Error),
+    // Nothing fits inside the boundary.
+    NoNodes,
+    Disconnected { unreachable_count: usize },
+    InvalidSeed(String),
+    // Bad input, such as an out of range index in a maze file.
+    ParseError { field: String, msg: String },
+    // A setting that would panic or produce an empty maze.
+    InvalidParams { field: String, msg: String },
+    // A maze that fails `verify_maze`.
+    Invalid(ValidationError),
+    // The SVG couldn't be drawn as an image.
+    Render(String),
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::Io(e) => e.fmt(f),
+            MazeError::SystemTime(e) => e.fmt(f),
+            MazeError::Json(e) => e.fmt(f),
+            MazeError::NoNodes => f.write_str("no nodes fit inside the boundary"),
+            MazeError::Disconnected { unreachable_count } => {
+                write!(f, "{unreachable_count} nodes can't be reached")
+            }
+            MazeError::InvalidSeed(seed) => write!(f, "invalid seed {seed:?}"),
+            MazeError::ParseError { field, msg } => write!(f, "{field}: {msg}"),
+            MazeError::InvalidParams { field, msg } => write!(f, "{field} {msg}"),
+            MazeError::Invalid(e) => e.fmt(f),
+            MazeError::Render(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for MazeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MazeError::Io(e) => Some(e),
+            MazeError::SystemTime(e) => Some(e),
+            MazeError::Json(e) => Some(e),
+            MazeError::Invalid(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::time::SystemTimeError> for MazeError {
+    fn from(e: std::time::SystemTimeError) -> Self {
+        Self::SystemTime(e)
+    }
+}
+
+impl From<std::io::Error> for MazeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for MazeError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<ValidationError> for MazeError {
+    fn from(e: ValidationError) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Edge(pub Index, pub Index);
+
+// For `#[serde(with = "maze::v2_serde")]` on `V2` fields, written as `{x, y}`.
+pub mod v2_serde {
+    use crate::V2;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Point {
+        x: f64,
+        y: f64,
+    }
+
+    pub fn serialize<S: Serializer>(v: &V2, serializer: S) -> Result<S::Ok, S::Error> {
+        Point { x: v.x, y: v.y }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<V2, D::Error> {
+        let Point { x, y } = Point::deserialize(deserializer)?;
+        Ok(V2 { x, y })
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[serde(from = "NodeRecord", into = "NodeRecord")]
+pub struct Node {
+    pub point: V2,
+    pub index: Index,
+}
+
+// How a node is written out, since `V2` has no serde support.
+#[derive(Serialize, Deserialize)]
+struct NodeRecord {
+    index: Index,
+    x: f64,
+    y: f64,
+}
+
+impl From<NodeRecord> for Node {
+    fn from(r: NodeRecord) -> Self {
+        Self {
+            point: V2 { x: r.x, y: r.y },
+            index: r.index,
+        }
+    }
+}
+
+impl From<Node> for NodeRecord {
+    fn from(n: Node) -> Self {
+        Self {
+            index: n.index,
+            x: n.point.x,
+            y: n.point.y,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Pol {
+    pub a: f64,
+    pub r: f64,
+}
+
+impl From<Pol> for V2 {
+    fn from(p: Pol) -> Self {
+        Self {
+            x: p.a.cos() * p.r,
+            y: p.a.sin() * p.r,
+        }
+    }
+}
+
+// The angle is in (-PI, PI].
+impl From<V2> for Pol {
+    fn from(v: V2) -> Self {
+        Self {
+            a: v.y.atan2(v.x),
+            r: v.x.hypot(v.y),
+        }
+    }
+}
+
+// Adds the angles and multiplies the radii, composing rotations and
+// scalings. This is complex multiplication, which `*` also spells.
+impl std::ops::Add for Pol {
+    type Output = Pol;
+
+    fn add(self, other: Pol) -> Pol {
+        Pol {
+            a: self.a + other.a,
+            r: self.r * other.r,
+        }
+    }
+}
+
+impl std::ops::Mul for Pol {
+    type Output = Pol;
+
+    fn mul(self, other: Pol) -> Pol {
+        Pol {
+            a: self.a + other.a,
+            r: self.r * other.r,
+        }
+    }
+}
+
+impl std::ops::Mul<f64> for Pol {
+    type Output = Pol;
+
+    fn mul(self, scale: f64) -> Pol {
+        Pol {
+            a: self.a,
+            r: self.r * scale,
+        }
+    }
+}
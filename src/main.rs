@@ -1,448 +1,787 @@
-use crate::seg::*;
+use clap::Parser;
 use hex_color::HexColor;
-use rand::seq::SliceRandom;
-use rand::Rng;
-use std::collections::HashSet;
-use std::f64::consts::{PI, TAU};
+use maze::*;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use std::time::{SystemTime, UNIX_EPOCH};
-use svg::node::element::path::Data;
-use svg::node::element::{Circle, Path};
-use svg::Document;
-use vector2d::Vector2D;
 
-mod seg;
-
-type V2 = Vector2D<f64>;
-type Result<T> = std::result::Result<T, Error>;
-const MAZE_RADIUS: f64 = 500.0;
-const TUBE_RADIUS: f64 = 0.005 * MAZE_RADIUS;
-const DRAW_FACTOR: f64 = 0.9;
-const MIN_SPACING: f64 = TUBE_RADIUS * 3.5;
-const TUBE_SHRINK: f64 = 0.15;
-const COMPUTE_TIME: Duration = Duration::from_secs(2);
+#[derive(Debug, Clone, Parser)]
+#[command(about = "Generate circular mazes as SVG images")]
+struct Cli {
+    /// Radius of the maze.
+    #[arg(long, default_value_t = 500.0)]
+    radius: f64,
+    /// Radius of the passages [default: radius * 0.005]
+    #[arg(long)]
+    tube_radius: Option<f64>,
+    /// Fraction of the tube radius used when drawing passages.
+    #[arg(long, default_value_t = 0.9)]
+    draw_factor: f64,
+    /// Minimum distance between nodes [default: tube-radius * 3.5]
+    #[arg(long)]
+    min_spacing: Option<f64>,
+    /// Seconds to spend placing nodes with the random layout.
+    #[arg(long, default_value_t = 2.0)]
+    time: f64,
+    /// Stop placing nodes at this many instead of after --time. The grid and
+    /// spiral layouts are cut short if they would place more.
+    #[arg(long, conflicts_with = "time")]
+    nodes: Option<usize>,
+    /// Number of nearest neighbors considered at each step.
+    #[arg(long, default_value_t = 12)]
+    neighbors: usize,
+    /// Largest allowed turn between passages, as a fraction of PI.
+    #[arg(long, default_value_t = 0.6)]
+    angle_cutoff: f64,
+    /// How turns are treated instead: none, hard:<radians> for a cutoff, or
+    /// soft:<strength> to favor gentle turns without ruling any out.
+    #[arg(long, conflicts_with = "angle_cutoff")]
+    angle_bias: Option<AngleBias>,
+    /// Which nodes passages may join: each node's --neighbors nearest, the
+    /// Delaunay triangulation, whose edges never cross, or the sparser Gabriel
+    /// graph within it.
+    #[arg(long, value_enum, default_value_t)]
+    connectivity: Connectivity,
+    /// Shortest passage dfs, bfs and growing tree may carve.
+    #[arg(long, default_value_t = 0.0)]
+    min_edge_length: f64,
+    /// Longest passage dfs, bfs and growing tree may carve.
+    #[arg(long, default_value_t = f64::INFINITY)]
+    max_edge_length: f64,
+    /// Most passages dfs, bfs and growing tree may join at one node: 2 for a
+    /// single winding path, 3 for T junctions at most.
+    #[arg(long)]
+    max_degree: Option<usize>,
+    /// Grow dfs mazes from loop-erased random walks, as Wilson's algorithm
+    /// does, for fewer long corridors and more short branches.
+    #[arg(long)]
+    loop_erase: bool,
+    /// Radius of a hole cut out of the middle of the maze.
+    #[arg(long, default_value_t = 0.0)]
+    inner_radius: f64,
+    #[arg(long, value_enum, default_value_t)]
+    shape: Shape,
+    /// Width of --shape rect [default: 2 * radius].
+    #[arg(long)]
+    width: Option<f64>,
+    /// Height of --shape rect [default: 2 * radius].
+    #[arg(long)]
+    height: Option<f64>,
+    /// Corners of --shape polygon, as "x1,y1;x2,y2;...".
+    #[arg(
+        long,
+        value_parser = parse_vertices,
+        allow_hyphen_values = true,
+        required_if_eq("shape", "polygon")
+    )]
+    vertices: Option<Vertices>,
+    /// Columns of cells for --shape grid-cells.
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    grid_width: u32,
+    /// Rows of cells for --shape grid-cells.
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..))]
+    grid_height: u32,
+    #[arg(long, value_enum, default_value_t)]
+    layout: NodeLayout,
+    /// CSV of x,y node positions for --layout csv, or a PNG whose white
+    /// parts get nodes for --layout mask.
+    #[arg(long, required_if_eq_any([("layout", "csv"), ("layout", "mask")]))]
+    layout_file: Option<PathBuf>,
+    #[arg(long, value_enum, default_value_t)]
+    algorithm: Algorithm,
+    /// Which active node --algorithm growing-tree extends next: newest,
+    /// oldest, random or mix:<probability of newest>.
+    #[arg(long, default_value = "newest")]
+    strategy: GrowthStrategy,
+    /// Where to start: top, bottom, left, right, random, farthest (from the
+    /// center of the nodes) or angle:<radians> [default: left, or the top
+    /// left corner of a polygon].
+    #[arg(long)]
+    start_at: Option<StartPlacement>,
+    /// Where to end: top, bottom, left, right, random, farthest (in hops from
+    /// the start) or angle:<radians> [default: the deepest point reached].
+    #[arg(long)]
+    end_at: Option<EndPlacement>,
+    /// Repeat the maze this many times around the center. Uses dfs whatever
+    /// the --algorithm.
+    #[arg(long)]
+    symmetry: Option<usize>,
+    /// Chance, from 0 to 1, that each dead end is joined to a neighbor.
+    #[arg(long, default_value_t = 0.0, value_parser = parse_fraction)]
+    braid: f64,
+    /// Chance, from 0 to 1, that each unjoined pair of neighbors gets a
+    /// passage, adding loops.
+    #[arg(long, default_value_t = 0.0, value_parser = parse_fraction)]
+    cycles: f64,
+    /// Check that the passages form a tree reaching every carved node.
+    /// Symmetric, braided and looped mazes aren't trees, so it can't be
+    /// combined with --symmetry, --braid or --cycles.
+    #[arg(long, conflicts_with_all = ["symmetry", "braid", "cycles"])]
+    validate: bool,
+    /// Draw only the largest connected part of the maze, moving the start
+    /// and end into it if they fall outside.
+    #[arg(long)]
+    lcc: bool,
+    /// Put the start and end at the two ends of the longest passage through
+    /// the maze, rather than the end at the deepest point of the search.
+    #[arg(long)]
+    auto_endpoints: bool,
+    /// Generate nodes on all cores. Only --layout grid uses it so far; see
+    /// --workers for making --count batches in parallel.
+    #[arg(long)]
+    parallel: bool,
+    /// Number of rings for --layout rings [default: one per min-spacing].
+    #[arg(long)]
+    rings: Option<usize>,
+    /// Seed for the random number generator [default: random]. The random
+    /// layout also depends on --time.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Draw the path from the start to the end, with the fewest edges (bfs)
+    /// or the shortest length (astar).
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "bfs")]
+    solve: Option<Solver>,
+    /// Colors for the background, passages, markers and solution.
+    #[arg(long, value_enum, default_value_t)]
+    theme: Theme,
+    /// Draw passages as curves rather than straight lines.
+    #[arg(long)]
+    curved: bool,
+    /// How far --curved passages bow out, as a fraction of their length.
+    #[arg(long, default_value_t = 0.2, value_parser = parse_curvature)]
+    curvature: f64,
+    /// Narrow passages toward dead ends and widen them at junctions.
+    #[arg(long)]
+    taper: bool,
+    /// Draw dark walls on white instead of the passages, for printing.
+    #[arg(long)]
+    walls: bool,
+    /// The SVG's <title> [default: Maze (algorithm=..., layout=..., seed=...)].
+    #[arg(long)]
+    title: Option<String>,
+    /// The SVG's <desc> [default: the shape and the node and edge counts].
+    #[arg(long)]
+    description: Option<String>,
+    /// How wide the maze will be when printed, such as 20cm, 150mm or 8in.
+    #[arg(long)]
+    physical_diameter: Option<PhysicalLength>,
+    /// Draw a scale bar in the bottom right corner for --physical-diameter.
+    #[arg(long, requires = "physical_diameter")]
+    scale_bar: bool,
+    /// Record the seed, algorithm, layout and sizes in the SVG's <metadata>.
+    #[arg(long)]
+    embed_metadata: bool,
+    /// Shade each node's Voronoi region behind the passages by its distance
+    /// from the start.
+    #[arg(long)]
+    voronoi: bool,
+    /// How passages are colored.
+    #[arg(long, value_enum, default_value_t)]
+    color_by: ColorBy,
+    /// Color of the shallowest passages with --color-by depth.
+    #[arg(long, default_value = "#003366")]
+    color_start: HexColor,
+    /// Color of the deepest passages with --color-by depth.
+    #[arg(long, default_value = "#FFFF00")]
+    color_end: HexColor,
+    /// Write each node and edge to stdout as a line of JSON while the maze
+    /// is generated.
+    #[arg(long)]
+    stream_json: bool,
+    /// Print nothing to stderr.
+    #[arg(short, long, conflicts_with_all = ["verbose", "progress"])]
+    quiet: bool,
+    /// Also log every node and passage as it is added. RUST_LOG overrides
+    /// -q and -v.
+    #[arg(short, long)]
+    verbose: bool,
+    /// Show progress bars on stderr while the maze is generated [default:
+    /// on when stdout is a terminal, except with --count or -v].
+    #[arg(long, overrides_with = "no_progress")]
+    progress: bool,
+    /// Hide the progress bars.
+    #[arg(long)]
+    no_progress: bool,
+    /// Print a summary of the maze to stdout.
+    #[arg(long)]
+    info: bool,
+    /// Draw the solution in gradually from the start to the end. Solves with
+    /// bfs unless --solve picks a solver.
+    #[arg(long)]
+    animate_solve: bool,
+    /// Seconds --animate-solve takes to draw the whole solution.
+    #[arg(long, default_value_t = 5.0)]
+    animate_duration: f64,
+    /// Print difficulty statistics for the maze to stdout as JSON.
+    #[arg(long)]
+    stats: bool,
+    /// Split the maze into this many wedges around the center and print
+    /// node and edge counts for each to stdout as JSON.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    sector_analysis: Option<u32>,
+    /// Formats to save, named like the SVG with their own extensions, in
+    /// place of the plain SVG. Repeat or separate with commas; all saves
+    /// every format. PNGs are 96 dpi, ascii is saved as 80x40 text and
+    /// edgelist has a line of `a b x_a y_a x_b y_b` per passage. tikz is a
+    /// 15 cm wide tikzpicture in the --theme colors for \input in LaTeX.
+    #[arg(long, value_enum, value_delimiter = ',')]
+    format: Vec<OutputFormat>,
+    /// Also save the maze graph as JSON, named like the SVG.
+    #[arg(long)]
+    json: bool,
+    /// Save the maze graph as JSON to this path instead.
+    #[arg(long)]
+    json_out: Option<PathBuf>,
+    /// Save the SVG gzipped, named like the SVG but ending in .svgz.
+    #[arg(long)]
+    svgz: bool,
+    /// Compression level for --svgz, from 0 (none) to 9 (smallest).
+    #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u32).range(0..=9))]
+    svgz_level: u32,
+    /// Directory for the SVG and the files named after it, created if
+    /// missing.
+    #[arg(long, default_value = ".")]
+    output_dir: PathBuf,
+    /// Name of the SVG, where {seed}, {timestamp}, {nodes} and {algorithm}
+    /// are filled in.
+    #[arg(long, default_value = "image-{timestamp}.svg")]
+    output_name: String,
+    /// Make this many mazes, with seeds counting up from --seed, and save
+    /// each under a name with its seed.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["stdout", "ascii", "stream_json", "json_out", "png_out"]
+    )]
+    count: Option<u32>,
+    /// With --count, also save per-maze statistics as batch-stats.json in
+    /// the output directory.
+    #[arg(long, requires = "count")]
+    batch_stats: bool,
+    /// With --count, make the mazes on this many threads at once [default:
+    /// one at a time].
+    #[arg(long, requires = "count", value_parser = clap::value_parser!(u32).range(1..))]
+    workers: Option<u32>,
+    /// Make the maze but don't write any files; print their names to stdout
+    /// and the maze's statistics to stderr instead.
+    #[arg(long, conflicts_with = "stdout")]
+    dry_run: bool,
+    /// Write a JSON summary of the maze next to the SVG, as <name>-report.json.
+    #[arg(long)]
+    report: bool,
+    /// Print the JSON summary of the maze to stdout.
+    #[arg(long, conflicts_with = "stdout")]
+    report_stdout: bool,
+    /// Print how long each phase of making the maze took to stderr.
+    #[arg(long)]
+    profile: bool,
+    /// Write the SVG to stdout instead of a file.
+    #[arg(long, conflicts_with_all = ["ascii", "svgz"])]
+    stdout: bool,
+    /// Also save the maze as a PNG image at this path.
+    #[arg(long)]
+    png_out: Option<PathBuf>,
+    /// Resolution of --png-out, where 96 maps one SVG unit to one pixel.
+    #[arg(long, default_value_t = 96.0)]
+    png_dpi: f64,
+    /// Also save the maze graph for Graphviz, named like the SVG.
+    #[arg(long)]
+    dot: bool,
+    /// Print the maze to the terminal as text instead of saving an SVG.
+    #[arg(long)]
+    ascii: bool,
+    /// Columns used by --ascii.
+    #[arg(
+        long,
+        default_value_t = 80,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    ascii_width: usize,
+    /// Rows used by --ascii.
+    #[arg(
+        long,
+        default_value_t = 40,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    ascii_height: usize,
+}
 
-#[derive(Debug)]
-pub struct Error(String);
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum)]
+enum Shape {
+    #[default]
+    Circle,
+    Rect,
+    Polygon,
+    /// A classic maze of square cells, ignoring the layout and algorithm.
+    GridCells,
+}
 
-#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
-pub struct Edge(pub Index, pub Index);
-impl From<std::time::SystemTimeError> for Error {
-    fn from(e: std::time::SystemTimeError) -> Self {
-        Self(e.to_string())
+fn parse_in_range(s: &str, low: f64, high: f64) -> std::result::Result<f64, String> {
+    let value: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if !(low..=high).contains(&value) {
+        return Err(format!("{value} is not between {low} and {high}"));
     }
+    Ok(value)
 }
 
-impl From<std::io::Error> for Error {
-    fn from(e: std::io::Error) -> Self {
-        Self(e.to_string())
-    }
+fn parse_fraction(s: &str) -> std::result::Result<f64, String> {
+    parse_in_range(s, 0.0, 1.0)
 }
 
-type Index = usize;
+fn parse_curvature(s: &str) -> std::result::Result<f64, String> {
+    parse_in_range(s, 0.0, 0.5)
+}
 
-#[derive(Debug, Copy, Clone)]
-struct Node {
-    point: V2,
-    index: Index,
+// A printed size, like 20cm.
+#[derive(Debug, Clone, PartialEq)]
+struct PhysicalLength {
+    value: f64,
+    unit: String,
 }
 
-fn gen_nodes_random(rng: &mut impl Rng) -> Vec<Node> {
-    let mut nodes: Vec<Node> = Vec::new();
-    let start_compute = Instant::now();
-    let mut tries = 0;
-    while Instant::now() - start_compute < COMPUTE_TIME {
-        let radians: f64 = rng.gen::<f64>() * TAU;
-        let radius: f64 = rng.gen::<f64>() * (MAZE_RADIUS - TUBE_RADIUS * (2f64).sqrt() * 2.0);
-        let point = V2 {
-            x: radians.cos() * radius,
-            y: radians.sin() * radius,
-        };
-        tries += 1;
-        if nodes
-            .iter()
-            .cloned()
-            .all(|Node { point: a, .. }| (a - point).length() > MIN_SPACING)
-        {
-            nodes.push(Node {
-                point,
-                index: nodes.len(),
-            });
+impl std::str::FromStr for PhysicalLength {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        let s = s.trim();
+        let unit = ["mm", "cm", "in"]
+            .into_iter()
+            .find(|unit| s.ends_with(unit))
+            .ok_or_else(|| format!("{s:?} needs a unit: mm, cm or in"))?;
+        let value: f64 = s[..s.len() - unit.len()]
+            .trim()
+            .parse()
+            .map_err(|e| format!("{s:?}: {e}"))?;
+        if !(value > 0.0 && value.is_finite()) {
+            return Err(format!("{s:?} must be positive"));
         }
+        Ok(Self {
+            value,
+            unit: unit.to_string(),
+        })
     }
-    eprintln!("scanned {} points, found {} points.", tries, nodes.len());
-    nodes
 }
 
-fn gen_nodes_spiral() -> Vec<Node> {
-    let mut nodes: Vec<Node> = Vec::new();
-    let mut phi = 0.0;
-    let mut radius = 0.0;
-    let mut index = 0;
-    loop {
-        radius += 0.1;
-        phi += 0.1;
-        let point: V2 = Pol { a: phi, r: radius }.into();
-        if nodes
-            .iter()
-            .cloned()
-            .all(|Node { point: a, .. }| (a - point).length() > MIN_SPACING)
-        {
-            nodes.push(Node { point, index });
-            // eprintln!("point={point:?}, count={}", nodes.len());
-            index += 1;
-        }
-        if point.length() > (MAZE_RADIUS - TUBE_RADIUS * (2f64).sqrt() * 2.0) {
-            break;
-        }
+// An alias so clap parses the whole list as one value rather than many.
+type Vertices = Vec<V2>;
+
+fn parse_vertices(s: &str) -> std::result::Result<Vertices, String> {
+    let vertices = s
+        .split(';')
+        .map(|pair| {
+            let (x, y) = pair
+                .split_once(',')
+                .ok_or_else(|| format!("expected x,y but got {pair:?}"))?;
+            let coord = |c: &str| c.trim().parse::<f64>().map_err(|e| format!("{c:?}: {e}"));
+            Ok(V2 {
+                x: coord(x)?,
+                y: coord(y)?,
+            })
+        })
+        .collect::<std::result::Result<Vertices, String>>()?;
+    if vertices.len() < 3 {
+        return Err("a polygon needs at least 3 vertices".to_string());
     }
-    nodes
+    Ok(vertices)
 }
 
-fn gen_nodes_grid() -> Vec<Node> {
-    let mut nodes: Vec<Node> = Vec::new();
-    let center = V2 { x: 0.0, y: 0.0 };
-    for y in -MAZE_RADIUS as i64..=MAZE_RADIUS as i64 {
-        for x in -MAZE_RADIUS as i64..=MAZE_RADIUS as i64 {
-            let point = V2 {
-                x: x as f64,
-                y: y as f64,
-            };
-            if point.length() > (MAZE_RADIUS - TUBE_RADIUS * (2f64).sqrt() * 2.0) {
-                continue;
-            }
-            if nodes
-                .iter()
-                .cloned()
-                .all(|Node { point: a, .. }| (a - point).length() > MIN_SPACING)
-            {
-                nodes.push(Node {
-                    point,
-                    index: nodes.len(),
-                });
-                // eprintln!("point={point:?}, count={}", nodes.len());
-            }
+impl Cli {
+    fn verbosity(&self) -> Verbosity {
+        if self.quiet {
+            Verbosity::Quiet
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+
+    fn boundary(&self) -> Option<BoundaryShape> {
+        match self.shape {
+            Shape::Circle | Shape::GridCells => None,
+            Shape::Rect => Some(BoundaryShape::Rectangle {
+                width: self.width.unwrap_or(2.0 * self.radius),
+                height: self.height.unwrap_or(2.0 * self.radius),
+            }),
+            Shape::Polygon => Some(BoundaryShape::Polygon {
+                vertices: self.vertices.clone().unwrap_or_default(),
+            }),
         }
     }
-    nodes
 }
 
 fn main() -> Result<()> {
-    let mut rng = rand::thread_rng();
-    let start: Node = Node {
-        index: 0,
-        point: Pol {
-            a: -PI,
-            r: MAZE_RADIUS + TUBE_RADIUS * 10.0,
+    let cli = Cli::parse();
+    env_logger::Builder::new()
+        .filter_level(cli.verbosity().level_filter())
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .parse_default_env()
+        .init();
+    let seed = cli.seed.unwrap_or_else(rand::random);
+    log::info!("seed: {seed}");
+    if let Some(count) = cli.count {
+        return run_batch(&cli, seed, count as u64);
+    }
+    let (_, saved) = run(&cli, &maze_builder(&cli, seed))?;
+    // With --stdout the SVG goes to stdout, so the names of any other files
+    // go to stderr.
+    for path in saved {
+        if cli.stdout {
+            eprintln!("{}", path.display());
+        } else {
+            println!("{}", path.display());
         }
-        .into(),
-    };
-    let nodes: Vec<Node> = gen_nodes_grid();
-    let path_color = "#111111";
-    let mut document = Document::new()
-        .set(
-            "viewBox",
-            (
-                -MAZE_RADIUS * 1.01,
-                -MAZE_RADIUS * 1.01,
-                2.0 * MAZE_RADIUS * 1.01,
-                2.0 * MAZE_RADIUS * 1.01,
-            ),
-        )
-        .set("style", format!("background-color: {path_color}").as_str());
-
-    let mut visited: HashSet<Index> = Default::default();
-    let mut edges: HashSet<Edge> = Default::default();
-    let start_point: Node = get_nearest_k(&nodes, start, 2)[0];
-    let mut midpoints: Vec<V2> = Vec::new();
-    let mut max_depth_index = (0, 0);
-    dfs(
-        &mut rng,
-        start_point.point - V2 { x: 10.0, y: 0.0 },
-        start_point,
-        &mut edges,
-        &mut visited,
-        &nodes,
-        &mut midpoints,
-        &mut max_depth_index,
-        0,
-    );
-    eprintln!("created {} edges", edges.len());
-    document = document.add(
-        Circle::new()
-            .set("r", MAZE_RADIUS)
-            .set("cx", 0.0)
-            .set("cy", 0.0)
-            .set("fill", rand_col().as_ref()),
-    );
+    }
+    Ok(())
+}
 
-    let drawn_nodes: HashSet<Index> = HashSet::new();
+// The builder for the maze the flags describe, with `seed`.
+fn maze_builder(cli: &Cli, seed: u64) -> MazeBuilder {
+    let tube_radius = cli.tube_radius.unwrap_or(0.005 * cli.radius);
+    let builder = MazeBuilder::new()
+        .radius(cli.radius)
+        .tube_radius(tube_radius)
+        .min_spacing(cli.min_spacing.unwrap_or(tube_radius * 3.5))
+        .compute_time(Duration::from_secs_f64(cli.time))
+        .neighbor_k(cli.neighbors)
+        .angle_cutoff(cli.angle_cutoff)
+        .connectivity(cli.connectivity)
+        .loop_erase(cli.loop_erase)
+        .min_edge_length(cli.min_edge_length)
+        .max_edge_length(cli.max_edge_length)
+        .inner_radius(cli.inner_radius)
+        .layout(cli.layout)
+        .algorithm(cli.algorithm)
+        .strategy(cli.strategy)
+        .braid(cli.braid)
+        .cycles(cli.cycles)
+        .parallel(cli.parallel)
+        .seed(seed);
+    let builder = match cli.boundary() {
+        Some(boundary) => builder.boundary(boundary),
+        None => builder,
+    };
+    let builder = match cli.nodes {
+        Some(nodes) => builder.target_nodes(nodes),
+        None => builder,
+    };
+    let builder = match cli.max_degree {
+        Some(max_degree) => builder.max_degree(max_degree),
+        None => builder,
+    };
+    let builder = match cli.angle_bias {
+        Some(angle_bias) => builder.angle_bias(angle_bias),
+        None => builder,
+    };
+    let builder = match cli.start_at {
+        Some(start_at) => builder.start_at(start_at),
+        None => builder,
+    };
+    let builder = match cli.end_at {
+        Some(end_at) => builder.end_at(end_at),
+        None => builder,
+    };
+    let builder = match &cli.layout_file {
+        Some(layout_file) => builder.layout_file(layout_file),
+        None => builder,
+    };
+    let builder = match cli.rings {
+        Some(rings) => builder.rings(rings),
+        None => builder,
+    };
+    match cli.symmetry {
+        Some(symmetry) => builder.symmetry(symmetry),
+        None => builder,
+    }
+}
 
-    for Edge(a, b) in edges {
-        let path_color = "white";
-        document = add_edge(document, nodes[a].point, nodes[b].point, path_color);
-        if !drawn_nodes.contains(&a) {
-            document = document.add(
-                Circle::new()
-                    .set("r", TUBE_RADIUS * DRAW_FACTOR)
-                    .set("cx", nodes[a].point.x)
-                    .set("cy", nodes[a].point.y)
-                    .set("fill", path_color),
+// Makes the maze from `builder` and writes out everything the flags ask for,
+// returning the maze and the files saved.
+fn run(cli: &Cli, builder: &MazeBuilder) -> Result<(Maze, Vec<PathBuf>)> {
+    let mut rng = builder.rng();
+    let mut timing = PhaseTiming::new();
+    let mut observers: Vec<Box<dyn MazeObserver + '_>> = Vec::new();
+    if cli.profile {
+        observers.push(Box::new(&mut timing));
+    }
+    if cli.stream_json {
+        observers.push(Box::new(JsonStreamSink::new()));
+    }
+    let verbosity = cli.verbosity();
+    let progress = cli.progress
+        || (cli.count.is_none()
+            && verbosity == Verbosity::Normal
+            && std::io::stdout().is_terminal());
+    if progress && !cli.no_progress {
+        observers.push(Box::new(ProgressObserver::new()));
+    }
+    if verbosity == Verbosity::Verbose {
+        observers.push(Box::new(LogObserver));
+    }
+    let generation_start = Instant::now();
+    let mut maze = match cli.shape {
+        Shape::GridCells => {
+            let mut maze = gen_maze_rectangular_grid_perfect(
+                cli.grid_width as usize,
+                cli.grid_height as usize,
+                &mut rng,
             );
+            maze.metadata.seed = builder.seed;
+            maze
         }
-        if !drawn_nodes.contains(&b) {
-            document = document.add(
-                Circle::new()
-                    .set("r", TUBE_RADIUS * DRAW_FACTOR)
-                    .set("cx", nodes[b].point.x)
-                    .set("cy", nodes[b].point.y)
-                    .set("fill", path_color),
-            );
+        _ => builder.build_with_observer(&mut rng, &mut observers)?,
+    };
+    let generation_time = generation_start.elapsed();
+    drop(observers);
+    if cli.shape == Shape::GridCells {
+        timing.record("Grid generation", generation_time);
+    }
+    if cli.lcc {
+        let (nodes, edges) = largest_connected_component(&maze.edges, maze.nodes.len());
+        let excluded = maze.nodes.len() - nodes.len();
+        if excluded > 0 {
+            log::warn!("warning: left out {excluded} nodes outside the largest component");
+        }
+        maze.edges = edges;
+        if !nodes.contains(&maze.start) || !nodes.contains(&maze.end) {
+            let graph = MazeGraph::from_edges(&maze.edges, maze.nodes.len());
+            let (_, start, end) = compute_diameter(&graph);
+            maze.start = start;
+            maze.end = end;
         }
     }
-    // Draw the start.
-    document = document.add(
-        Circle::new()
-            .set("r", TUBE_RADIUS * 1.25)
-            .set("cx", start_point.point.x)
-            .set("cy", start_point.point.y)
-            .set("fill", "green"),
-    );
-    // Draw the end.
-    document = document.add(
-        Circle::new()
-            .set("r", TUBE_RADIUS * 1.25)
-            .set("cx", nodes[max_depth_index.1].point.x)
-            .set("cy", nodes[max_depth_index.1].point.y)
-            .set("fill", "red"),
-    );
-
-    /*
-    for Node {
-        point: V2 { x, y }, ..
-    } in nodes.iter()
-    {
-        document = document.add(
-            Circle::new()
-                .set("r", TUBE_RADIUS)
-                .set("cx", *x)
-                .set("cy", *y)
-                .set("fill", HexColor::random_rgba().to_string().as_str()), // "white"),
-        );
+    if cli.auto_endpoints {
+        let graph = MazeGraph::from_edges(&maze.edges, maze.nodes.len());
+        let (_, start, end) = compute_diameter(&graph);
+        maze.start = start;
+        maze.end = end;
     }
-    */
-    let svg_filename = format!(
-        "image-{}.svg",
-        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()
-    );
-
-    svg::save(svg_filename.clone(), &document)?;
-    println!("{}", svg_filename);
-    Ok(())
-}
-fn rand_col() -> String {
-    HexColor::random_rgb().to_string()
-}
-fn get_nearest_k(nodes: &[Node], cur: Node, k: usize) -> Vec<Node> {
-    let mut nodes: Vec<Node> = nodes.to_vec();
-    nodes.sort_by(|a, b| {
-        let a_dist: f64 = (a.point - cur.point).length_squared();
-        let b_dist: f64 = (b.point - cur.point).length_squared();
-        a_dist.partial_cmp(&b_dist).unwrap()
+    if cli.validate {
+        verify_maze(&maze)?;
+    }
+    if cli.info {
+        println!("{maze}");
+    }
+    if cli.stats {
+        let analysis = analyze_maze(&maze.edges, &maze.nodes, maze.start, maze.end);
+        println!("{}", serde_json::to_string(&analysis)?);
+    }
+    if let Some(sectors) = cli.sector_analysis {
+        let stats = partition_by_sector(&maze.nodes, &maze.edges, sectors as usize);
+        println!("{}", serde_json::to_string(&stats)?);
+    }
+    let svg_start = Instant::now();
+    let mut opts = RenderOptions {
+        draw_factor: cli.draw_factor,
+        boundary_color: rand_col(&mut rng),
+        color_by: cli.color_by,
+        curvature: if cli.curved { cli.curvature } else { 0.0 },
+        taper: cli.taper,
+        animate_solve: cli.animate_solve.then_some(cli.animate_duration),
+        color_start: cli.color_start,
+        color_end: cli.color_end,
+        voronoi: cli.voronoi,
+        metadata: cli.embed_metadata,
+        scale_bar: cli
+            .physical_diameter
+            .as_ref()
+            .filter(|_| cli.scale_bar)
+            .map(|diameter| {
+                let (top_left, bottom_right) = maze.params.boundary().bounds();
+                ScaleBar {
+                    pixels_per_unit: (bottom_right.x - top_left.x) / diameter.value,
+                    unit: diameter.unit.clone(),
+                }
+            }),
+        ..apply_theme(cli.theme)
+    };
+    if cli.walls {
+        opts = apply_walls(opts);
+    }
+    let solver = match cli.solve {
+        None if cli.animate_solve => Some(Solver::Bfs),
+        solver => solver,
+    };
+    let solution = solver.and_then(|solver| {
+        let path = solve(solver, &maze.edges, &maze.nodes, maze.start, maze.end);
+        if path.is_none() {
+            log::warn!("warning: no path from the start to the end");
+        }
+        path
     });
-    nodes.truncate(k);
-    nodes
-}
+    let mut document = match solution {
+        Some(path) => render_svg_solved(&maze, &path, &opts),
+        None => render_svg(&maze, &opts),
+    };
+    let title = cli
+        .title
+        .clone()
+        .unwrap_or_else(|| default_svg_title(&maze.metadata));
+    let description = cli
+        .description
+        .clone()
+        .unwrap_or_else(|| default_svg_description(&maze));
+    document = add_svg_title(document, &title, &description);
+    timing.record("SVG construction", svg_start.elapsed());
+    let io_start = Instant::now();
 
-#[allow(clippy::too_many_arguments)]
-fn dfs(
-    rng: &mut impl Rng,
-    prior: V2,
-    current: Node,
-    edges: &mut HashSet<Edge>,
-    visited: &mut HashSet<Index>,
-    nodes: &Vec<Node>,
-    midpoints: &mut Vec<V2>,
-    max_depth_index: &mut (usize, usize),
-    depth: usize,
-) {
-    let cur_vec_angle = (current.point - prior).normalise().angle();
-    let mut nearest_nodes = get_nearest_k(nodes, current, 12);
-    nearest_nodes.shuffle(rng);
-    for node in nearest_nodes {
-        if !visited.contains(&node.index) {
-            let edge = Edge(current.index, node.index);
-            let edge_vec = (node.point - current.point).normalise();
-            let diff = radian_diff(edge_vec.angle(), cur_vec_angle);
-            if diff > PI * 0.6 {
-                // println!("bailing AAAAA");
-                continue;
-            }
-            if edge_intersects(edge, edges, nodes) {
-                continue;
-            }
-            let midpoint = (node.point + current.point) * 0.5;
-            if midpoints
-                .iter()
-                .all(|&m| (m - midpoint).length() > MIN_SPACING * 0.8)
-                && nodes.iter().all(|n| {
-                    n.index == node.index
-                        || n.index == current.index
-                        || (n.point - midpoint).length() > TUBE_RADIUS * 2.0
-                })
-            {
-                if depth > max_depth_index.0 {
-                    *max_depth_index = (depth, node.index);
-                }
-                midpoints.push(midpoint);
-                visited.insert(node.index);
-                edges.insert(edge);
-                dfs(
-                    rng,
-                    current.point,
-                    node,
-                    edges,
-                    visited,
-                    nodes,
-                    midpoints,
-                    max_depth_index,
-                    depth + 1,
-                );
-            } else {
-                // println!( "bailing BBBBB midpoint={midpoint:?}, node={:?}, current={:?}", node.point, current.point);
-            }
+    let svg_filename = cli
+        .output_dir
+        .join(expand_output_template(&cli.output_name, &maze.metadata));
+    // With --dry-run nothing is written, but the names are still returned.
+    let write = !cli.dry_run;
+    if let Some(dir) = svg_filename.parent().filter(|_| write) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut saved: Vec<PathBuf> = Vec::new();
+    if cli.ascii {
+        print!("{}", render_ascii(&maze, cli.ascii_width, cli.ascii_height));
+    } else if cli.stdout {
+        svg::write(std::io::stdout().lock(), &document)?;
+    } else if cli.svgz {
+        let svgz_filename = svg_filename.with_extension("svgz");
+        if write {
+            save_svgz_with_level(&document, &svgz_filename, cli.svgz_level)?;
         }
+        saved.push(svgz_filename);
+    } else if cli.format.is_empty() {
+        if write {
+            svg::save(&svg_filename, &document)?;
+        }
+        saved.push(svg_filename.clone());
     }
-}
-#[derive(Debug, Copy, Clone)]
-struct QueueItem {
-    prior: V2,
-    current: Node,
-    next: Node,
-    depth: usize,
-}
-fn enqueue_nearest(
-    rng: &mut impl Rng,
-    prior: V2,
-    nodes: &[Node],
-    current: Node,
-    k: usize,
-    depth: usize,
-    queue: &mut Vec<QueueItem>,
-) {
-    // if depth > 15 { return; }
-    let mut nearest_nodes = get_nearest_k(nodes, current, k);
-    nearest_nodes.shuffle(rng);
-    for node in nearest_nodes {
-        queue.push(QueueItem {
-            prior,
-            current,
-            next: node,
-            depth,
-        });
+    for format in OutputFormat::expand(&cli.format) {
+        if write {
+            write_output_with_options(&maze, &document, &opts, format, &svg_filename)?;
+        }
+        saved.push(svg_filename.with_extension(format.extension()));
     }
-    // queue.shuffle(rng);
-}
-#[allow(clippy::too_many_arguments)]
-fn bfs(
-    rng: &mut impl Rng,
-    prior: V2,
-    current: Node,
-    edges: &mut HashSet<Edge>,
-    visited: &mut HashSet<Index>,
-    nodes: &[Node],
-    midpoints: &mut Vec<V2>,
-    max_depth_index: &mut (usize, usize),
-) {
-    let mut queue: Vec<QueueItem> = Default::default();
-    enqueue_nearest(rng, prior, nodes, current, 12, 1, &mut queue);
-    while let Some(&QueueItem {
-        prior,
-        current,
-        next: node,
-        depth,
-    }) = queue.get(0)
-    {
-        queue.remove(0);
-        let cur_vec_angle = (current.point - prior).normalise().angle();
-        if !visited.contains(&node.index) {
-            let edge = Edge(current.index, node.index);
-            let edge_vec = (node.point - current.point).normalise();
-            let diff = radian_diff(edge_vec.angle(), cur_vec_angle);
-            if diff > PI * 0.8 {
-                continue;
-            }
-            if edge_intersects(edge, edges, nodes) {
-                continue;
-            }
-            let midpoint = (node.point + current.point) * 0.5;
-            if midpoints
-                .iter()
-                .all(|&m| (m - midpoint).length() > MIN_SPACING * 0.8)
-                && nodes
-                    .iter()
-                    .all(|n| (n.point - midpoint).length() > TUBE_RADIUS * 2.1)
-            {
-                if depth > max_depth_index.0 {
-                    *max_depth_index = (depth, node.index);
+    if let Some(png_filename) = &cli.png_out {
+        if !write {
+            saved.push(png_filename.clone());
+        } else {
+            match export_png(&document.to_string(), cli.png_dpi) {
+                Ok(png) => {
+                    std::fs::write(png_filename, png)?;
+                    saved.push(png_filename.clone());
                 }
-                midpoints.push(midpoint);
-                visited.insert(node.index);
-                edges.insert(edge);
-                enqueue_nearest(rng, current.point, nodes, node, 12, depth + 1, &mut queue);
+                Err(e) => log::warn!("warning: skipping the PNG: {e}"),
             }
         }
     }
-}
-fn radian_diff(a: f64, b: f64) -> f64 {
-    let mut d = a - b;
-    if d > PI {
-        d -= TAU;
-    } else if d < -PI {
-        d += TAU;
-    }
-    d.abs()
-}
-fn edge_intersects(edge: Edge, edges: &HashSet<Edge>, nodes: &[Node]) -> bool {
-    let Edge(a, b) = edge;
-    for &Edge(c, d) in edges {
-        if intersection_with_width(
-            nodes[a].point,
-            nodes[b].point,
-            nodes[c].point,
-            nodes[d].point,
-            TUBE_RADIUS,
-            TUBE_SHRINK,
-        ) {
-            return true;
+    if cli.json || cli.json_out.is_some() {
+        let json_filename = cli
+            .json_out
+            .clone()
+            .unwrap_or_else(|| svg_filename.with_extension("json"));
+        if write {
+            std::fs::write(&json_filename, serde_json::to_string(&export_json(&maze))?)?;
         }
+        saved.push(json_filename);
     }
-    false
-}
-fn add_edge(document: Document, start: V2, end: V2, color: &str) -> Document {
-    // eprintln!("[add_edge] start={start:?} end={end:?}");
-    let data = Data::new()
-        .move_to((start.x, start.y))
-        .line_to((end.x, end.y));
-    let path = Path::new()
-        .set("fill", color)
-        .set("stroke", color)
-        .set("stroke-width", TUBE_RADIUS * DRAW_FACTOR * 2.0)
-        .set("d", data);
-    document.add(path)
+    if cli.dot {
+        let dot_filename = svg_filename.with_extension("dot");
+        if write {
+            let mut writer = BufWriter::new(File::create(&dot_filename)?);
+            export_dot(&maze, &mut writer)?;
+            writer.flush()?;
+        }
+        saved.push(dot_filename);
+    }
+    if cli.report || cli.report_stdout {
+        let report = maze_report(&maze, generation_time, document.to_string().len());
+        let json = serde_json::to_string_pretty(&report)?;
+        if cli.report_stdout {
+            println!("{json}");
+        }
+        if cli.report {
+            let stem = svg_filename
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let report_filename = svg_filename.with_file_name(format!("{stem}-report.json"));
+            if write {
+                std::fs::write(&report_filename, json)?;
+            }
+            saved.push(report_filename);
+        }
+    }
+    timing.record("File I/O", io_start.elapsed());
+    if cli.profile {
+        print_timing_table(&timing);
+    }
+    if cli.dry_run {
+        let analysis = analyze_maze(&maze.edges, &maze.nodes, maze.start, maze.end);
+        log::info!(
+            "nodes: {}, edges: {}, {}",
+            maze.nodes.len(),
+            maze.edges.len(),
+            serde_json::to_string(&analysis)?
+        );
+    }
+    Ok((maze, saved))
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Pol {
-    pub a: f64,
-    pub r: f64,
+// One line of the --batch-stats summary.
+#[derive(Serialize)]
+struct BatchEntry {
+    seed: u64,
+    files: Vec<PathBuf>,
+    node_count: usize,
+    dead_ends: usize,
+    // Zero when the end can't be reached from the start.
+    solution_hops: usize,
 }
 
-impl From<Pol> for V2 {
-    fn from(p: Pol) -> Self {
-        Self {
-            x: p.a.cos() * p.r,
-            y: p.a.sin() * p.r,
+// Runs `count` mazes with successive seeds from `seed`, each named after its
+// seed, and prints the files saved in seed order.
+fn run_batch(cli: &Cli, seed: u64, count: u64) -> Result<()> {
+    let mut output_name = cli.output_name.clone();
+    if !output_name.contains("{seed}") {
+        let name = Path::new(&output_name);
+        output_name = match (name.file_stem(), name.extension()) {
+            (Some(stem), Some(ext)) => name
+                .with_file_name(format!(
+                    "{}-{{seed}}.{}",
+                    stem.to_string_lossy(),
+                    ext.to_string_lossy()
+                ))
+                .to_string_lossy()
+                .into_owned(),
+            _ => format!("{output_name}-{{seed}}"),
+        };
+    }
+    let cli = Cli {
+        output_name,
+        ..cli.clone()
+    };
+    let seeds: Vec<u64> = (0..count).map(|i| seed.wrapping_add(i)).collect();
+    let workers = cli.workers.map_or(1, |workers| workers as usize);
+    let results = maze_builder(&cli, seed)
+        .build_batch_with(&seeds, workers, |builder| run(&cli, &builder))?;
+    let mut entries: Vec<BatchEntry> = Vec::new();
+    for (seed, (maze, saved)) in results {
+        for path in &saved {
+            println!("{}", path.display());
         }
+        let analysis = analyze_maze(&maze.edges, &maze.nodes, maze.start, maze.end);
+        entries.push(BatchEntry {
+            seed,
+            files: saved,
+            node_count: maze.nodes.len(),
+            dead_ends: analysis.dead_end_count,
+            solution_hops: analysis.solution_hops,
+        });
     }
+    if cli.batch_stats {
+        let stats_filename = cli.output_dir.join("batch-stats.json");
+        if !cli.dry_run {
+            std::fs::write(&stats_filename, serde_json::to_string(&entries)?)?;
+        }
+        println!("{}", stats_filename.display());
+    }
+    Ok(())
 }
@@ -0,0 +1,823 @@
+use crate::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct Params {
+    pub radius: f64,
+    pub tube_radius: f64,
+    pub min_spacing: f64,
+    pub compute_time: Duration,
+    // Node count to stop at instead of running out of `compute_time`.
+    pub target_nodes: Option<usize>,
+    // The random layout stops early once the share of its tries that place a
+    // node, averaged over roughly the last 1000, falls below this.
+    pub min_accept_rate: f64,
+    pub neighbor_k: usize,
+    // Fraction of PI beyond which a turn is rejected.
+    pub angle_cutoff: f64,
+    // Radius of the hole cut out of the middle; zero for a full disk.
+    pub inner_radius: f64,
+    // Outline of the maze; a circle of `radius` when None.
+    pub boundary: Option<BoundaryShape>,
+    // How dfs and bfs treat turns; a hard cutoff at `angle_cutoff` when None.
+    pub angle_bias: Option<AngleBias>,
+    // Which nodes dfs, bfs and growing tree may link.
+    pub connectivity: Connectivity,
+    // Bounds on the passages dfs, bfs and growing tree may carve.
+    pub min_edge_length: f64,
+    pub max_edge_length: f64,
+    // Most passages dfs, bfs and growing tree may join at one node.
+    pub max_degree: Option<usize>,
+    // Whether dfs grows the maze by loop-erased random walks, as Wilson's
+    // algorithm does, instead of one long search.
+    pub loop_erase: bool,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        let radius = 500.0;
+        let tube_radius = 0.005 * radius;
+        Self {
+            radius,
+            tube_radius,
+            min_spacing: tube_radius * 3.5,
+            compute_time: Duration::from_secs(2),
+            target_nodes: None,
+            min_accept_rate: 0.001,
+            neighbor_k: 12,
+            angle_cutoff: 0.6,
+            inner_radius: 0.0,
+            boundary: None,
+            angle_bias: None,
+            connectivity: Connectivity::default(),
+            min_edge_length: 0.0,
+            max_edge_length: f64::INFINITY,
+            max_degree: None,
+            loop_erase: false,
+        }
+    }
+}
+
+impl Params {
+    // Largest distance from the origin at which a node may be placed in a
+    // circular maze.
+    pub fn node_radius(&self) -> f64 {
+        self.radius - self.margin()
+    }
+
+    // How far nodes stay from the boundary.
+    pub fn margin(&self) -> f64 {
+        self.tube_radius * (2f64).sqrt() * 2.0
+    }
+
+    pub fn boundary(&self) -> Cow<'_, BoundaryShape> {
+        match &self.boundary {
+            Some(shape) => Cow::Borrowed(shape),
+            None => Cow::Owned(BoundaryShape::Circle {
+                radius: self.radius,
+            }),
+        }
+    }
+
+    // Whether a node may be placed at `point`.
+    pub fn admits(&self, point: V2) -> bool {
+        !self.in_hole(point) && self.boundary().contains(point, self.margin())
+    }
+
+    // Sharpest turn, in radians, that dfs and bfs may take.
+    pub fn turn_limit(&self) -> f64 {
+        match self.angle_bias {
+            None => PI * self.angle_cutoff,
+            Some(AngleBias::Hard { cutoff }) => cutoff,
+            Some(AngleBias::None | AngleBias::Soft { .. }) => f64::INFINITY,
+        }
+    }
+
+    // Whether `point` is too close to the central hole to hold a node.
+    pub fn in_hole(&self, point: V2) -> bool {
+        self.inner_radius > 0.0 && point.length() < self.inner_radius + self.tube_radius * 2.0
+    }
+}
+
+// Candidates tried around each sample by the Poisson disk layout.
+pub const POISSON_K: u32 = 30;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NodeLayout {
+    #[default]
+    Grid,
+    Spiral,
+    Random,
+    Hex,
+    Poisson,
+    Rings,
+    Halton,
+    Fibonacci,
+    // Read from `MazeBuilder::layout_file`.
+    Csv,
+    // Poisson disk sampling over the white parts of the PNG in
+    // `MazeBuilder::layout_file`.
+    Mask,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    #[default]
+    Dfs,
+    Bfs,
+    Wilson,
+    Prim,
+    Kruskal,
+    AldousBroder,
+    HuntAndKill,
+    GrowingTree,
+    RecursiveDivision,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Connectivity {
+    // Each node's `neighbor_k` nearest nodes.
+    #[default]
+    Knn,
+    // The edges of the Delaunay triangulation, which never cross.
+    Delaunay,
+    // The Delaunay edges whose diameter circle holds no other node.
+    Gabriel,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(into = "MazeJson", try_from = "MazeJson")]
+pub struct Maze {
+    pub nodes: Vec<Node>,
+    pub edges: HashSet<Edge>,
+    pub start: Index,
+    pub end: Index,
+    pub metadata: MazeMeta,
+    pub params: Params,
+    // How deep into the traversal each edge was added.
+    pub depths: HashMap<Edge, usize>,
+}
+
+impl Maze {
+    // Hops from the start to the node farthest from it.
+    pub fn depth(&self) -> usize {
+        self.distances().into_iter().flatten().max().unwrap_or(0)
+    }
+
+    // Hops from the start to each node, or None where it can't be reached.
+    pub fn distances(&self) -> Vec<Option<usize>> {
+        hop_counts(&self.edges, self.nodes.len(), self.start)
+    }
+
+    pub fn sorted_edges(&self) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = self.edges.iter().cloned().collect();
+        edges.sort_by_key(|&Edge(a, b)| (a, b));
+        edges
+    }
+}
+
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let analysis = analyze_maze(&self.edges, &self.nodes, self.start, self.end);
+        let average_degree = if self.nodes.is_empty() {
+            0.0
+        } else {
+            2.0 * self.edges.len() as f64 / self.nodes.len() as f64
+        };
+        writeln!(f, "nodes: {}", self.nodes.len())?;
+        writeln!(f, "edges: {}", self.edges.len())?;
+        writeln!(f, "start: {}", self.start)?;
+        writeln!(f, "end: {}", self.end)?;
+        writeln!(f, "radius: {}", self.metadata.radius)?;
+        writeln!(f, "average degree: {average_degree:.3}")?;
+        writeln!(f, "dead ends: {}", analysis.dead_end_count)?;
+        write!(f, "depth: {}", self.depth())
+    }
+}
+
+// Edges are sorted so the output is the same on every run with a seed.
+impl fmt::Debug for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Maze")
+            .field("nodes", &self.nodes.len())
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .field("metadata", &self.metadata)
+            .field("edges", &self.sorted_edges())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MazeBuilder {
+    pub params: Params,
+    pub layout: NodeLayout,
+    pub algorithm: Algorithm,
+    pub seed: Option<u64>,
+    pub strategy: GrowthStrategy,
+    // Ring count for the rings layout; by default rings are min_spacing apart.
+    pub rings: Option<usize>,
+    // Rotational symmetry; the maze repeats this many times around the center.
+    pub symmetry: Option<usize>,
+    // Chance that each dead end gets an extra passage.
+    pub braid: f64,
+    // Chance that each unjoined pair of neighbors gets a passage.
+    pub cycles: f64,
+    // Spread node generation across threads where the layout supports it.
+    pub parallel: bool,
+    // Candidates per sample for the Poisson layout [default: POISSON_K].
+    pub poisson_k: Option<u32>,
+    // CSV of x,y node positions for the csv layout, or the PNG for the mask
+    // layout.
+    pub layout_file: Option<PathBuf>,
+    // Neighbors per node walked by Aldous-Broder [default: ALDOUS_BRODER_K].
+    pub aldous_broder_k: Option<usize>,
+    // By default circles start on the left and other shapes at the top left.
+    pub start_at: Option<StartPlacement>,
+    // By default the end is the deepest point of the search, the hole in an
+    // annulus, or the corner opposite the start.
+    pub end_at: Option<EndPlacement>,
+}
+
+impl MazeBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn params(mut self, params: Params) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn radius(mut self, radius: f64) -> Self {
+        self.params.radius = radius;
+        self
+    }
+
+    pub fn tube_radius(mut self, tube_radius: f64) -> Self {
+        self.params.tube_radius = tube_radius;
+        self
+    }
+
+    pub fn min_spacing(mut self, min_spacing: f64) -> Self {
+        self.params.min_spacing = min_spacing;
+        self
+    }
+
+    pub fn compute_time(mut self, compute_time: Duration) -> Self {
+        self.params.compute_time = compute_time;
+        self
+    }
+
+    pub fn target_nodes(mut self, target_nodes: usize) -> Self {
+        self.params.target_nodes = Some(target_nodes);
+        self
+    }
+
+    pub fn min_accept_rate(mut self, min_accept_rate: f64) -> Self {
+        self.params.min_accept_rate = min_accept_rate;
+        self
+    }
+
+    pub fn neighbor_k(mut self, neighbor_k: usize) -> Self {
+        self.params.neighbor_k = neighbor_k;
+        self
+    }
+
+    pub fn angle_cutoff(mut self, angle_cutoff: f64) -> Self {
+        self.params.angle_cutoff = angle_cutoff;
+        self
+    }
+
+    pub fn connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.params.connectivity = connectivity;
+        self
+    }
+
+    pub fn min_edge_length(mut self, min_edge_length: f64) -> Self {
+        self.params.min_edge_length = min_edge_length;
+        self
+    }
+
+    pub fn max_edge_length(mut self, max_edge_length: f64) -> Self {
+        self.params.max_edge_length = max_edge_length;
+        self
+    }
+
+    pub fn max_degree(mut self, max_degree: usize) -> Self {
+        self.params.max_degree = Some(max_degree);
+        self
+    }
+
+    pub fn loop_erase(mut self, loop_erase: bool) -> Self {
+        self.params.loop_erase = loop_erase;
+        self
+    }
+
+    pub fn angle_bias(mut self, angle_bias: AngleBias) -> Self {
+        self.params.angle_bias = Some(angle_bias);
+        self
+    }
+
+    pub fn inner_radius(mut self, inner_radius: f64) -> Self {
+        self.params.inner_radius = inner_radius;
+        self
+    }
+
+    pub fn boundary(mut self, boundary: BoundaryShape) -> Self {
+        self.params.boundary = Some(boundary);
+        self
+    }
+
+    pub fn layout(mut self, layout: NodeLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    pub fn strategy(mut self, strategy: GrowthStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn rings(mut self, rings: usize) -> Self {
+        self.rings = Some(rings);
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: usize) -> Self {
+        self.symmetry = Some(symmetry);
+        self
+    }
+
+    pub fn braid(mut self, braid: f64) -> Self {
+        self.braid = braid;
+        self
+    }
+
+    pub fn cycles(mut self, cycles: f64) -> Self {
+        self.cycles = cycles;
+        self
+    }
+
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    pub fn poisson_k(mut self, poisson_k: u32) -> Self {
+        self.poisson_k = Some(poisson_k);
+        self
+    }
+
+    pub fn layout_file(mut self, layout_file: impl Into<PathBuf>) -> Self {
+        self.layout_file = Some(layout_file.into());
+        self
+    }
+
+    pub fn aldous_broder_k(mut self, aldous_broder_k: usize) -> Self {
+        self.aldous_broder_k = Some(aldous_broder_k);
+        self
+    }
+
+    pub fn start_at(mut self, start_at: StartPlacement) -> Self {
+        self.start_at = Some(start_at);
+        self
+    }
+
+    pub fn end_at(mut self, end_at: EndPlacement) -> Self {
+        self.end_at = Some(end_at);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    // A generator seeded from `seed`, or from entropy when no seed was given.
+    pub fn rng(&self) -> ChaCha8Rng {
+        match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(seed),
+            None => ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    pub fn gen_nodes(&self, rng: &mut impl Rng) -> Result<Vec<Node>> {
+        self.gen_nodes_with_observer(rng, &mut NullObserver)
+    }
+
+    // Like `gen_nodes`, letting the random layout report its progress.
+    pub fn gen_nodes_with_observer(
+        &self,
+        rng: &mut impl Rng,
+        observer: &mut dyn MazeObserver,
+    ) -> Result<Vec<Node>> {
+        Ok(match self.layout {
+            NodeLayout::Grid if self.parallel => gen_nodes_grid_parallel(&self.params),
+            NodeLayout::Grid => gen_nodes_grid(&self.params),
+            NodeLayout::Spiral => gen_nodes_spiral(&self.params),
+            NodeLayout::Random => gen_nodes_random_with_observer(rng, &self.params, observer),
+            NodeLayout::Hex => gen_nodes_hex(&self.params),
+            NodeLayout::Poisson => {
+                gen_nodes_poisson(rng, &self.params, self.poisson_k.unwrap_or(POISSON_K))
+            }
+            NodeLayout::Rings => {
+                let rings = self.rings.unwrap_or_else(|| {
+                    (self.params.node_radius() / self.params.min_spacing) as usize
+                });
+                gen_nodes_rings(&self.params, rings.max(1))
+            }
+            NodeLayout::Halton => {
+                gen_nodes_halton(&self.params, self.params.target_nodes.unwrap_or(usize::MAX))
+            }
+            NodeLayout::Fibonacci => gen_nodes_fibonacci(&self.params),
+            NodeLayout::Csv | NodeLayout::Mask => {
+                let Some(path) = &self.layout_file else {
+                    return Err(MazeError::InvalidParams {
+                        field: "layout file".to_string(),
+                        msg: "the csv and mask layouts need a layout file".to_string(),
+                    });
+                };
+                if self.layout == NodeLayout::Csv {
+                    gen_nodes_from_csv(&self.params, path)?
+                } else {
+                    let k = self.poisson_k.unwrap_or(POISSON_K);
+                    gen_nodes_from_mask(rng, &self.params, path, None, k)?
+                }
+            }
+        })
+    }
+
+    // Rejects settings that would panic or produce an empty maze.
+    pub fn validate(&self) -> Result<()> {
+        let params = &self.params;
+        let positive = [
+            ("radius", params.radius),
+            ("tube radius", params.tube_radius),
+            ("min spacing", params.min_spacing),
+        ];
+        if let Some((name, value)) = positive.iter().find(|(_, v)| !(*v > 0.0 && v.is_finite())) {
+            return Err(MazeError::InvalidParams {
+                field: name.to_string(),
+                msg: format!("must be positive, not {value}"),
+            });
+        }
+        if params.neighbor_k == 0 {
+            return Err(MazeError::InvalidParams {
+                field: "neighbor k".to_string(),
+                msg: "must be at least 1".to_string(),
+            });
+        }
+        if params.max_degree == Some(0) {
+            return Err(MazeError::InvalidParams {
+                field: "max degree".to_string(),
+                msg: "must be at least 1".to_string(),
+            });
+        }
+        if !(params.min_edge_length >= 0.0 && params.min_edge_length <= params.max_edge_length) {
+            return Err(MazeError::InvalidParams {
+                field: "edge length".to_string(),
+                msg: format!(
+                    "min {} must be at least 0 and at most max {}",
+                    params.min_edge_length, params.max_edge_length
+                ),
+            });
+        }
+        if !(0.0..params.radius).contains(&params.inner_radius) {
+            return Err(MazeError::InvalidParams {
+                field: "inner radius".to_string(),
+                msg: format!(
+                    "{} must be at least 0 and less than the radius {}",
+                    params.inner_radius, params.radius
+                ),
+            });
+        }
+        for (name, value) in [("braid", self.braid), ("cycles", self.cycles)] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(MazeError::InvalidParams {
+                    field: name.to_string(),
+                    msg: format!("{value} is not between 0 and 1"),
+                });
+            }
+        }
+        match &params.boundary {
+            Some(BoundaryShape::Polygon { vertices }) if vertices.len() < 3 => {
+                Err(MazeError::InvalidParams {
+                    field: "polygon".to_string(),
+                    msg: "needs at least 3 vertices".to_string(),
+                })
+            }
+            Some(BoundaryShape::Rectangle { width, height })
+                if !(*width > 0.0 && *height > 0.0) =>
+            {
+                Err(MazeError::InvalidParams {
+                    field: "rectangle".to_string(),
+                    msg: format!("{width}x{height} is empty"),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn build(&self, rng: &mut impl Rng) -> Result<Maze> {
+        self.build_with_observer(rng, &mut NullObserver)
+    }
+
+    // A maze for each of `seeds`, in the same order, built on `workers`
+    // threads, or one per core when `workers` is 0. Each gets its own builder
+    // and generator, so the result is the same as building them one at a
+    // time.
+    pub fn build_batch(&self, seeds: &[u64], workers: usize) -> Result<Vec<(u64, Maze)>> {
+        self.build_batch_with(seeds, workers, |builder| builder.build(&mut builder.rng()))
+    }
+
+    // Like `build_batch`, but hands each seed's builder to `job` in place of
+    // building it, for callers that also render or save each maze.
+    pub fn build_batch_with<T: Send>(
+        &self,
+        seeds: &[u64],
+        workers: usize,
+        job: impl Fn(MazeBuilder) -> Result<T> + Sync,
+    ) -> Result<Vec<(u64, T)>> {
+        // One worker runs on this thread, which leaves the global pool free
+        // for `parallel` node generation.
+        if workers == 1 {
+            return seeds
+                .iter()
+                .map(|&seed| Ok((seed, job(self.clone().seed(seed))?)))
+                .collect();
+        }
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(workers)
+            .build()
+            .map_err(std::io::Error::other)?;
+        pool.install(|| {
+            seeds
+                .par_iter()
+                .map(|&seed| Ok((seed, job(self.clone().seed(seed))?)))
+                .collect()
+        })
+    }
+
+    // Like `build`, reporting each step to `observer`. dfs and bfs report
+    // visits and edges as they find them; the other algorithms, and braiding
+    // and cycles, once they are done.
+    pub fn build_with_observer(
+        &self,
+        rng: &mut impl Rng,
+        observer: &mut dyn MazeObserver,
+    ) -> Result<Maze> {
+        self.validate()?;
+        let params = &self.params;
+        let symmetry = self.symmetry.filter(|&s| s > 1);
+        let mut phase_start = Instant::now();
+        let mut nodes = self.gen_nodes_with_observer(rng, observer)?;
+        if nodes.is_empty() {
+            return Err(MazeError::NoNodes);
+        }
+        if let Some(symmetry) = symmetry {
+            nodes = symmetrize_nodes(params, &nodes, symmetry);
+        }
+        observer.on_phase_done("Node generation", phase_start.elapsed());
+        for node in &nodes {
+            observer.on_node_added(node);
+        }
+        // Circles are entered from the left, other shapes from the top left
+        // corner of their bounding box.
+        let boundary = params.boundary();
+        let (top_left, bottom_right) = boundary.bounds();
+        let entry: Node = Node {
+            index: 0,
+            point: match *boundary {
+                BoundaryShape::Circle { radius } => Pol {
+                    a: -PI,
+                    r: radius + params.tube_radius * 10.0,
+                }
+                .into(),
+                _ => top_left,
+            },
+        };
+        let (start_point, prior) = match self.start_at {
+            Some(start_at) => {
+                let start_point = place_start_node(&nodes, start_at, rng);
+                // Enter heading in toward the center, or rightward from it.
+                let outward = match start_point.point.length() {
+                    0.0 => V2 { x: -1.0, y: 0.0 },
+                    length => start_point.point / length,
+                };
+                (start_point, start_point.point + outward * 10.0)
+            }
+            None => {
+                let start_point: Node = get_nearest_k(&nodes, entry, 2)[0];
+                (start_point, start_point.point - V2 { x: 10.0, y: 0.0 })
+            }
+        };
+
+        observer.on_node_visited(&start_point, 0);
+
+        // dfs and bfs track the deepest node they reach; the other algorithms
+        // end at the node farthest from the start.
+        let mut deepest: Option<Index> = None;
+        let mut depths: HashMap<Edge, usize> = HashMap::new();
+        phase_start = Instant::now();
+        let mut edges = match self.algorithm {
+            _ if symmetry.is_some() => {
+                gen_maze_symmetric(rng, params, &nodes, start_point, symmetry.unwrap())
+            }
+            Algorithm::Dfs | Algorithm::Bfs => {
+                let mut visited: HashSet<Index> = Default::default();
+                let mut edges: HashSet<Edge> = Default::default();
+                let mut midpoints: Vec<V2> = Vec::new();
+                let mut max_depth_index = (0, 0);
+                visited.insert(start_point.index);
+                let cache = NeighborCache::for_params(&nodes, params);
+                observer.on_phase_done("Neighbor cache", phase_start.elapsed());
+                phase_start = Instant::now();
+                let traverse = if self.algorithm == Algorithm::Dfs {
+                    dfs
+                } else {
+                    bfs
+                };
+                traverse(
+                    rng,
+                    params,
+                    prior,
+                    start_point,
+                    &mut edges,
+                    &mut visited,
+                    &nodes,
+                    &cache,
+                    &mut midpoints,
+                    &mut max_depth_index,
+                    &mut depths,
+                    observer,
+                );
+                deepest = Some(max_depth_index.1);
+                edges
+            }
+            Algorithm::Wilson => tree_containing(
+                gen_maze_wilson(rng, params, &nodes),
+                nodes.len(),
+                start_point.index,
+            ),
+            Algorithm::Prim => gen_maze_prim(rng, params, &nodes, start_point),
+            Algorithm::Kruskal => tree_containing(
+                gen_maze_kruskal(rng, params, &nodes),
+                nodes.len(),
+                start_point.index,
+            ),
+            Algorithm::AldousBroder => gen_maze_aldous_broder(
+                rng,
+                params,
+                &nodes,
+                start_point,
+                self.aldous_broder_k.unwrap_or(ALDOUS_BRODER_K),
+            ),
+            Algorithm::HuntAndKill => gen_maze_hunt_and_kill(rng, params, &nodes, start_point),
+            Algorithm::RecursiveDivision => tree_containing(
+                gen_maze_recursive_division(rng, params, &nodes, RECURSIVE_DIVISION_K),
+                nodes.len(),
+                start_point.index,
+            ),
+            Algorithm::GrowingTree => {
+                gen_maze_growing_tree(rng, params, &nodes, start_point, self.strategy)
+            }
+        };
+        let carving = match self.algorithm {
+            _ if symmetry.is_some() => "Symmetric DFS".to_string(),
+            Algorithm::Dfs => "DFS traversal".to_string(),
+            Algorithm::Bfs => "BFS traversal".to_string(),
+            algorithm => format!("Carving ({})", value_name(&algorithm)),
+        };
+        observer.on_phase_done(&carving, phase_start.elapsed());
+        let reported: HashSet<Edge> = depths.keys().cloned().collect();
+        if self.braid > 0.0 || self.cycles > 0.0 {
+            phase_start = Instant::now();
+            if self.braid > 0.0 {
+                braid_maze(rng, params, &mut edges, &nodes, self.braid);
+            }
+            if self.cycles > 0.0 {
+                add_cycles(rng, params, &mut edges, &nodes, self.cycles);
+            }
+            observer.on_phase_done("Braid and cycles", phase_start.elapsed());
+        }
+        phase_start = Instant::now();
+        let mut unreported: Vec<Edge> = edges.difference(&reported).cloned().collect();
+        unreported.sort_by_key(|&Edge(a, b)| (a, b));
+        for edge in unreported {
+            observer.on_edge_added(edge, &nodes);
+        }
+        let reachable = bfs_order(&edges, nodes.len(), start_point.index);
+        let visited: HashSet<Index> = reported.iter().flat_map(|e| [e.0, e.1]).collect();
+        let hops = hop_counts(&edges, nodes.len(), start_point.index);
+        for &i in &reachable {
+            if i != start_point.index && !visited.contains(&i) {
+                observer.on_node_visited(&nodes[i], hops[i].unwrap_or(0));
+            }
+        }
+        // With a hole in the middle, the maze leads from the rim to the hole;
+        // shapes entered at a corner lead to the opposite one.
+        let closest_to = |target: V2| {
+            *reachable
+                .iter()
+                .min_by(|&&a, &&b| {
+                    let a = (nodes[a].point - target).length();
+                    let b = (nodes[b].point - target).length();
+                    a.total_cmp(&b)
+                })
+                .unwrap()
+        };
+        let end = if let Some(end_at) = self.end_at {
+            place_end_node(&nodes, &edges, start_point.index, end_at, rng)
+        } else if params.inner_radius > 0.0 {
+            closest_to(V2 { x: 0.0, y: 0.0 })
+        } else if !matches!(*boundary, BoundaryShape::Circle { .. }) {
+            closest_to(bottom_right)
+        } else {
+            deepest.unwrap_or(*reachable.last().unwrap())
+        };
+        let depths = edge_depths(&edges, nodes.len(), start_point.index, depths);
+        observer.on_phase_done("Endpoints and depths", phase_start.elapsed());
+        let node_count = nodes.len();
+        let maze = Maze {
+            nodes,
+            edges,
+            start: start_point.index,
+            end,
+            metadata: MazeMeta {
+                radius: params.radius,
+                tube_radius: params.tube_radius,
+                seed: self.seed,
+                algorithm: Some(self.algorithm),
+                layout: Some(self.layout),
+                min_spacing: Some(params.min_spacing),
+                node_count,
+            },
+            params: params.clone(),
+            depths,
+        };
+        observer.on_complete(&maze);
+        log::info!("created {} edges", maze.edges.len());
+        Ok(maze)
+    }
+}
+
+// Fills in `depths` for edges the traversal didn't record, such as those from
+// the other algorithms or braiding, with the hops from `start` to their
+// nearer end.
+pub fn edge_depths(
+    edges: &HashSet<Edge>,
+    node_count: usize,
+    start: Index,
+    mut depths: HashMap<Edge, usize>,
+) -> HashMap<Edge, usize> {
+    let hops = hop_counts(edges, node_count, start);
+    for &edge @ Edge(a, b) in edges {
+        depths.entry(edge).or_insert(match (hops[a], hops[b]) {
+            (Some(x), Some(y)) => x.min(y),
+            (x, y) => x.or(y).unwrap_or(0),
+        });
+    }
+    depths
+}
+
+// Breadth-first hop counts from `start` along `edges`.
+pub fn hop_counts(edges: &HashSet<Edge>, node_count: usize, start: Index) -> Vec<Option<usize>> {
+    MazeGraph::from_edges(edges, node_count).hops_from(start)
+}
+
+// Nodes reachable from `start` along `edges`, in breadth-first order.
+fn bfs_order(edges: &HashSet<Edge>, node_count: usize, start: Index) -> Vec<Index> {
+    let graph = MazeGraph::from_edges(edges, node_count);
+    let mut seen = vec![false; node_count];
+    let mut queue = VecDeque::from([start]);
+    let mut order = Vec::new();
+    seen[start] = true;
+    while let Some(u) = queue.pop_front() {
+        order.push(u);
+        for &v in graph.neighbors(u) {
+            if !seen[v] {
+                seen[v] = true;
+                queue.push_back(v);
+            }
+        }
+    }
+    order
+}
@@ -0,0 +1,771 @@
+use crate::*;
+use rand::Rng;
+use rayon::prelude::*;
+use std::f64::consts::{PI, TAU};
+use std::path::Path;
+use std::time::Instant;
+
+// A point in the bounding box of `boundary` for `u` and `v` in [0, 1).
+fn point_in_bounds(boundary: &BoundaryShape, u: f64, v: f64) -> V2 {
+    let (top_left, bottom_right) = boundary.bounds();
+    V2 {
+        x: top_left.x + u * (bottom_right.x - top_left.x),
+        y: top_left.y + v * (bottom_right.y - top_left.y),
+    }
+}
+
+// Farthest distance from the origin at which a node could be placed.
+fn reach(params: &Params, boundary: &BoundaryShape) -> f64 {
+    match *boundary {
+        BoundaryShape::Circle { radius } => radius - params.margin(),
+        _ => {
+            let (top_left, bottom_right) = boundary.bounds();
+            top_left.length().max(bottom_right.length())
+        }
+    }
+}
+
+// Tries over which `gen_nodes_random` averages its accept rate.
+pub const ACCEPT_RATE_WINDOW: f64 = 1000.0;
+
+// Random points `gen_nodes_poisson` tries for its seed before deciding that
+// nothing fits, as when the inner hole covers nearly all the disk.
+pub const POISSON_SEED_TRIES: usize = 10_000;
+
+// Places nodes for `compute_time`, or until there are `target_nodes` of them
+// when that's set. Either way it stops early once the boundary is so full
+// that the accept rate drops below `min_accept_rate`.
+pub fn gen_nodes_random(rng: &mut impl Rng, params: &Params) -> Vec<Node> {
+    gen_nodes_random_with_observer(rng, params, &mut NullObserver)
+}
+
+// Like `gen_nodes_random`, reporting the count to `observer` as nodes are
+// placed.
+pub fn gen_nodes_random_with_observer(
+    rng: &mut impl Rng,
+    params: &Params,
+    observer: &mut dyn MazeObserver,
+) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let boundary = params.boundary();
+    let start_compute = Instant::now();
+    let mut tries = 0;
+    // An exponential moving average, starting out optimistic.
+    let mut accept_rate = 1.0;
+    let done = |nodes: &[Node]| match params.target_nodes {
+        Some(target) => nodes.len() >= target,
+        None => Instant::now() - start_compute >= params.compute_time,
+    };
+    while !done(&nodes) && accept_rate >= params.min_accept_rate {
+        let point: V2 = match *boundary {
+            BoundaryShape::Circle { radius } => Pol {
+                a: rng.gen::<f64>() * TAU,
+                r: rng.gen::<f64>() * (radius - params.margin()),
+            }
+            .into(),
+            _ => point_in_bounds(&boundary, rng.gen(), rng.gen()),
+        };
+        tries += 1;
+        let accepted = params.admits(point) && !spatial.any_within(point, params.min_spacing);
+        if accepted {
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+            observer.on_nodes_placed(nodes.len(), false);
+        }
+        accept_rate += (f64::from(u8::from(accepted)) - accept_rate) / ACCEPT_RATE_WINDOW;
+    }
+    observer.on_nodes_placed(nodes.len(), true);
+    log::info!("scanned {} points, found {} points.", tries, nodes.len());
+    log::debug!(
+        "accept rate {:.5}, {:.1}% of the nodes a hexagonal packing would fit",
+        accept_rate,
+        100.0 * packing_fraction(params, nodes.len())
+    );
+    nodes
+}
+
+// `count` as a share of the most nodes `min_spacing` apart that could fit,
+// which is the area they can go in over the area of a hexagonal lattice cell.
+pub fn packing_fraction(params: &Params, count: usize) -> f64 {
+    let boundary = params.boundary();
+    let area = match *boundary {
+        BoundaryShape::Circle { .. } => PI * params.node_radius().powi(2),
+        _ => boundary.area(),
+    } - PI * params.inner_radius.powi(2);
+    let cell = 3f64.sqrt() / 2.0 * params.min_spacing.powi(2);
+    count as f64 * cell / area
+}
+
+pub fn gen_nodes_spiral(params: &Params) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let mut phi = 0.0;
+    let mut radius = 0.0;
+    let mut index = 0;
+    let reach = reach(params, &params.boundary());
+    loop {
+        radius += 0.1;
+        phi += 0.1;
+        let point: V2 = Pol { a: phi, r: radius }.into();
+        if params.admits(point) && !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            nodes.push(Node { point, index });
+            index += 1;
+        }
+        if point.length() > reach {
+            break;
+        }
+    }
+    nodes.truncate(params.target_nodes.unwrap_or(usize::MAX));
+    nodes
+}
+
+pub fn gen_nodes_grid(params: &Params) -> Vec<Node> {
+    let (top_left, bottom_right) = params.boundary().bounds();
+    let candidates = (top_left.y as i64..=bottom_right.y as i64).flat_map(|y| {
+        (top_left.x as i64..=bottom_right.x as i64).map(move |x| V2 {
+            x: x as f64,
+            y: y as f64,
+        })
+    });
+    space_out(params, candidates.filter(|&point| params.admits(point)))
+}
+
+// Same nodes as `gen_nodes_grid`, but the boundary checks run on all cores.
+pub fn gen_nodes_grid_parallel(params: &Params) -> Vec<Node> {
+    let (top_left, bottom_right) = params.boundary().bounds();
+    let candidates: Vec<V2> = (top_left.y as i64..=bottom_right.y as i64)
+        .into_par_iter()
+        .flat_map_iter(|y| {
+            (top_left.x as i64..=bottom_right.x as i64).map(move |x| V2 {
+                x: x as f64,
+                y: y as f64,
+            })
+        })
+        .filter(|&point| params.admits(point))
+        .collect();
+    space_out(params, candidates)
+}
+
+// Keeps each candidate, in order, that is at least min_spacing from the ones
+// kept before it, up to `target_nodes`. Grid candidates come a row at a time,
+// so a target cuts off the bottom of the boundary.
+fn space_out(params: &Params, candidates: impl IntoIterator<Item = V2>) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let target = params.target_nodes.unwrap_or(usize::MAX);
+    for point in candidates {
+        if nodes.len() >= target {
+            break;
+        }
+        if !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+        }
+    }
+    nodes
+}
+
+// A triangular lattice with `min_spacing` between neighbors, every other row
+// shifted by half a step.
+pub fn gen_nodes_hex(params: &Params) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let spacing = params.min_spacing;
+    let row_height = spacing * 3f64.sqrt() / 2.0;
+    let boundary = params.boundary();
+    let (top_left, bottom_right) = boundary.bounds();
+    let rows = (top_left.y.abs().max(bottom_right.y.abs()) / row_height) as i64;
+    let cols = (top_left.x.abs().max(bottom_right.x.abs()) / spacing) as i64 + 1;
+    for row in -rows..=rows {
+        let offset = if row % 2 == 0 { spacing / 2.0 } else { 0.0 };
+        for col in -cols..=cols {
+            let point = V2 {
+                x: col as f64 * spacing + offset,
+                y: row as f64 * row_height,
+            };
+            if boundary.contains(point, params.margin()) && !params.in_hole(point) {
+                nodes.push(Node {
+                    point,
+                    index: nodes.len(),
+                });
+            }
+        }
+    }
+    nodes
+}
+
+// The i-th element of the van der Corput sequence in `base`.
+fn radical_inverse(mut i: u64, base: u64) -> f64 {
+    let mut result = 0.0;
+    let mut scale = 1.0 / base as f64;
+    while i > 0 {
+        result += (i % base) as f64 * scale;
+        i /= base;
+        scale /= base as f64;
+    }
+    result
+}
+
+// Like `gen_nodes_random`, but draws candidates from the 2D Halton sequence
+// (bases 2 and 3) mapped onto the disk, which spreads them more evenly.
+pub fn gen_nodes_halton(params: &Params, max_nodes: usize) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let boundary = params.boundary();
+    let start_compute = Instant::now();
+    let mut i: u64 = 0;
+    while nodes.len() < max_nodes && Instant::now() - start_compute < params.compute_time {
+        i += 1;
+        let point: V2 = match *boundary {
+            BoundaryShape::Circle { radius } => Pol {
+                a: radical_inverse(i, 3) * TAU,
+                r: radical_inverse(i, 2).sqrt() * (radius - params.margin()),
+            }
+            .into(),
+            _ => point_in_bounds(&boundary, radical_inverse(i, 2), radical_inverse(i, 3)),
+        };
+        if params.admits(point) && !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+        }
+    }
+    nodes
+}
+
+// Bridson's Poisson disk sampling: grows outward from a random seed point,
+// trying `k` candidates in the annulus [min_spacing, 2 * min_spacing] around
+// each active sample before retiring it. Returns no nodes if no seed is
+// found in `POISSON_SEED_TRIES` tries.
+pub fn gen_nodes_poisson(rng: &mut impl Rng, params: &Params, k: u32) -> Vec<Node> {
+    let min_dist = params.min_spacing;
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(min_dist);
+    let boundary = params.boundary();
+    let seed = (0..POISSON_SEED_TRIES)
+        .map(|_| match *boundary {
+            BoundaryShape::Circle { radius } => Pol {
+                a: rng.gen::<f64>() * TAU,
+                r: rng.gen::<f64>().sqrt() * (radius - params.margin()),
+            }
+            .into(),
+            _ => point_in_bounds(&boundary, rng.gen(), rng.gen()),
+        })
+        .find(|&seed| params.admits(seed));
+    let Some(seed) = seed else {
+        return nodes;
+    };
+    poisson_grow(
+        rng,
+        k,
+        min_dist,
+        seed,
+        |p| params.admits(p),
+        &mut nodes,
+        &mut spatial,
+    );
+    nodes
+}
+
+// Adds `seed` to `nodes` and grows Bridson's sampling out from it over the
+// points `admits` allows.
+fn poisson_grow(
+    rng: &mut impl Rng,
+    k: u32,
+    min_dist: f64,
+    seed: V2,
+    admits: impl Fn(V2) -> bool,
+    nodes: &mut Vec<Node>,
+    spatial: &mut SpatialHash,
+) {
+    spatial.insert(seed);
+    let mut active: Vec<Index> = vec![nodes.len()];
+    nodes.push(Node {
+        point: seed,
+        index: nodes.len(),
+    });
+    while !active.is_empty() {
+        let slot = rng.gen_range(0..active.len());
+        let center = nodes[active[slot]].point;
+        let mut found = false;
+        for _ in 0..k {
+            // Uniform over the annulus' area.
+            let r = min_dist * (1.0 + 3.0 * rng.gen::<f64>()).sqrt();
+            let point = center
+                + V2::from(Pol {
+                    a: rng.gen::<f64>() * TAU,
+                    r,
+                });
+            if !admits(point) || spatial.any_within(point, min_dist) {
+                continue;
+            }
+            spatial.insert(point);
+            active.push(nodes.len());
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+            found = true;
+            break;
+        }
+        if !found {
+            active.swap_remove(slot);
+        }
+    }
+}
+
+// Poisson disk sampling over the white parts of a PNG that the boundary also
+// admits. The image is centered on the origin at `scale` maze units per
+// pixel; None fits it to the boundary's bounding box. Each white region
+// not yet covered gets its own seed, so separate shapes like the letters of
+// a word all fill in.
+pub fn gen_nodes_from_mask(
+    rng: &mut impl Rng,
+    params: &Params,
+    image_path: &Path,
+    scale: Option<f64>,
+    k: u32,
+) -> Result<Vec<Node>> {
+    let mask = image::open(image_path).map_err(image_error)?.to_luma8();
+    let (width, height) = (mask.width() as f64, mask.height() as f64);
+    let scale = scale.unwrap_or_else(|| {
+        let (top_left, bottom_right) = params.boundary().bounds();
+        let size = bottom_right - top_left;
+        (size.x / width).min(size.y / height)
+    });
+    let white = |point: V2| {
+        let x = (point.x / scale + width / 2.0).floor();
+        let y = (point.y / scale + height / 2.0).floor();
+        (0.0..width).contains(&x)
+            && (0.0..height).contains(&y)
+            && mask.get_pixel(x as u32, y as u32).0[0] >= 128
+    };
+    let admits = |point: V2| white(point) && params.admits(point);
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    for (x, y, pixel) in mask.enumerate_pixels() {
+        if pixel.0[0] < 128 {
+            continue;
+        }
+        let seed = V2 {
+            x: (x as f64 + 0.5 - width / 2.0) * scale,
+            y: (y as f64 + 0.5 - height / 2.0) * scale,
+        };
+        if admits(seed) && !spatial.any_within(seed, params.min_spacing) {
+            poisson_grow(
+                rng,
+                k,
+                params.min_spacing,
+                seed,
+                admits,
+                &mut nodes,
+                &mut spatial,
+            );
+        }
+    }
+    Ok(nodes)
+}
+
+fn image_error(e: image::ImageError) -> MazeError {
+    match e {
+        image::ImageError::IoError(e) => MazeError::Io(e),
+        e => MazeError::ParseError {
+            field: "layout file".to_string(),
+            msg: e.to_string(),
+        },
+    }
+}
+
+// Nodes on `num_rings` concentric circles reaching the boundary, plus one at
+// the center, each ring holding as many evenly spaced nodes as fit
+// `min_spacing` apart.
+pub fn gen_nodes_rings(params: &Params, num_rings: usize) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    // Pulled in a hair so rounding doesn't push the outer ring past the edge.
+    let reach = reach(params, &params.boundary()) - 1e-9;
+    for ring in 0..=num_rings {
+        let r = ring as f64 * reach / num_rings as f64;
+        let count = if ring == 0 {
+            1
+        } else if params.min_spacing >= 2.0 * r {
+            continue;
+        } else {
+            (PI / (params.min_spacing / (2.0 * r)).asin()).floor() as usize
+        };
+        for i in 0..count {
+            let point: V2 = Pol {
+                a: TAU * i as f64 / count as f64,
+                r,
+            }
+            .into();
+            // Rings closer together than `min_spacing` would crowd each other.
+            if !params.admits(point) || spatial.any_within(point, params.min_spacing * 0.99) {
+                continue;
+            }
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+        }
+    }
+    nodes
+}
+
+// Sunflower (phyllotaxis) spiral: node i sits at radius R * sqrt(i / n) and
+// angle i * 2pi / phi^2. Giving each node 1.2 * min_spacing^2 of area keeps
+// nearly all of them min_spacing apart; with less, the filter drops many.
+pub fn gen_nodes_fibonacci(params: &Params) -> Vec<Node> {
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let golden_ratio = (1.0 + 5f64.sqrt()) / 2.0;
+    let radius = reach(params, &params.boundary());
+    let n_max = (PI * radius * radius / (1.2 * params.min_spacing * params.min_spacing)) as usize;
+    for i in 0..=n_max {
+        let point: V2 = Pol {
+            a: i as f64 * TAU / (golden_ratio * golden_ratio),
+            r: radius * (i as f64 / n_max as f64).sqrt(),
+        }
+        .into();
+        if params.admits(point) && !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+        }
+    }
+    nodes
+}
+
+// Nodes at the `x,y` rows of a CSV file, in file order, keeping those the
+// boundary admits and at least min_spacing from the ones kept before them.
+// A first row that isn't two numbers is taken as a header.
+pub fn gen_nodes_from_csv(params: &Params, path: &Path) -> Result<Vec<Node>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(csv::Trim::All)
+        .from_path(path)
+        .map_err(csv_error)?;
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    for (row, record) in reader.records().enumerate() {
+        let record = record.map_err(csv_error)?;
+        let coordinate = |i: usize| record.get(i).and_then(|s| s.parse::<f64>().ok());
+        let point = match (record.len(), coordinate(0), coordinate(1)) {
+            (2, Some(x), Some(y)) if x.is_finite() && y.is_finite() => V2 { x, y },
+            _ if row == 0 => continue,
+            _ => {
+                return Err(MazeError::ParseError {
+                    field: "layout file".to_string(),
+                    msg: format!(
+                        "line {}: expected x,y, found {:?}",
+                        row + 1,
+                        record.as_slice()
+                    ),
+                })
+            }
+        };
+        if params.admits(point) && !spatial.any_within(point, params.min_spacing) {
+            spatial.insert(point);
+            nodes.push(Node {
+                point,
+                index: nodes.len(),
+            });
+        }
+    }
+    Ok(nodes)
+}
+
+fn csv_error(e: csv::Error) -> MazeError {
+    let msg = e.to_string();
+    match e.into_kind() {
+        csv::ErrorKind::Io(e) => MazeError::Io(e),
+        _ => MazeError::ParseError {
+            field: "layout file".to_string(),
+            msg,
+        },
+    }
+}
+
+// `params` with its boundary swapped for a `width` x `height` rectangle
+// centered on the origin, as --shape rect sets it.
+fn rect_params(params: &Params, width: f64, height: f64) -> Params {
+    Params {
+        boundary: Some(BoundaryShape::Rectangle { width, height }),
+        ..params.clone()
+    }
+}
+
+// Like `gen_nodes_grid`, but fills a `width` x `height` rectangle centered on
+// the origin.
+pub fn gen_nodes_rect(params: &Params, width: f64, height: f64) -> Vec<Node> {
+    gen_nodes_grid(&rect_params(params, width, height))
+}
+
+// A grid exactly `min_spacing` apart, symmetric about the origin, filling a
+// `width` x `height` rectangle.
+pub fn gen_nodes_rect_grid(params: &Params, width: f64, height: f64) -> Vec<Node> {
+    let params = rect_params(params, width, height);
+    let cols = (width / 2.0 / params.min_spacing).floor() as i64;
+    let rows = (height / 2.0 / params.min_spacing).floor() as i64;
+    let mut nodes: Vec<Node> = Vec::new();
+    for y in -rows..=rows {
+        for x in -cols..=cols {
+            let point = V2 {
+                x: x as f64 * params.min_spacing,
+                y: y as f64 * params.min_spacing,
+            };
+            if params.admits(point) {
+                nodes.push(Node {
+                    point,
+                    index: nodes.len(),
+                });
+            }
+        }
+    }
+    nodes
+}
+
+// Keeps the nodes in the first 2pi / symmetry sector whose rotated copies all
+// fit, then adds those copies so that rotating by 2pi / symmetry maps node i
+// to node (i + n / symmetry) % n.
+pub fn symmetrize_nodes(params: &Params, nodes: &[Node], symmetry: usize) -> Vec<Node> {
+    let sector = TAU / symmetry as f64;
+    let mut spatial = SpatialHash::new(params.min_spacing);
+    let mut base: Vec<V2> = Vec::new();
+    for node in nodes {
+        if node.point.y.atan2(node.point.x).rem_euclid(TAU) >= sector {
+            continue;
+        }
+        let copies: Vec<V2> = (0..symmetry)
+            .map(|k| node.point.rotate(k as f64 * sector))
+            .collect();
+        // Copies of points near the center crowd each other.
+        if (copies[1] - copies[0]).length() <= params.min_spacing
+            || copies
+                .iter()
+                .any(|&c| !params.admits(c) || spatial.any_within(c, params.min_spacing))
+        {
+            continue;
+        }
+        for &c in &copies {
+            spatial.insert(c);
+        }
+        base.push(node.point);
+    }
+    (0..symmetry)
+        .flat_map(|k| base.iter().map(move |&p| p.rotate(k as f64 * sector)))
+        .enumerate()
+        .map(|(index, point)| Node { point, index })
+        .collect()
+}
+
+#[cfg(test)]
+fn min_distance(nodes: &[Node]) -> f64 {
+    let mut min = f64::INFINITY;
+    for (i, a) in nodes.iter().enumerate() {
+        for b in &nodes[i + 1..] {
+            min = min.min((a.point - b.point).length());
+        }
+    }
+    min
+}
+
+#[test]
+fn test_target_nodes() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        target_nodes: Some(50),
+        ..Default::default()
+    };
+    for seed in 0..5 {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(seed);
+        assert_eq!(gen_nodes_random(&mut rng, &params).len(), 50);
+    }
+    assert_eq!(gen_nodes_grid(&params).len(), 50);
+    assert_eq!(gen_nodes_grid_parallel(&params).len(), 50);
+    assert_eq!(gen_nodes_spiral(&params).len(), 50);
+
+    // The disk fills up at around 90, so placement gives up short of 100.
+    let crowded = Params {
+        target_nodes: Some(100),
+        ..params
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+    let count = gen_nodes_random(&mut rng, &crowded).len();
+    assert!((50..100).contains(&count), "{count}");
+}
+
+#[test]
+fn test_gen_nodes_random_stops_when_full() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        compute_time: std::time::Duration::from_secs(60),
+        ..Default::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+    let start = Instant::now();
+    let nodes = gen_nodes_random(&mut rng, &params);
+    assert!(start.elapsed() < params.compute_time / 2);
+    // Random packing jams at around 60% of the hexagonal density, but the
+    // accept rate hits 0.001 a little before that.
+    let fraction = packing_fraction(&params, nodes.len());
+    assert!((0.4..0.8).contains(&fraction), "{fraction}");
+}
+
+#[test]
+fn test_gen_nodes_grid_parallel() {
+    let params = Params {
+        radius: 80.0,
+        inner_radius: 20.0,
+        ..Default::default()
+    };
+    let expected = gen_nodes_grid(&params);
+    let actual = gen_nodes_grid_parallel(&params);
+    assert_eq!(actual.len(), expected.len());
+    assert!(actual
+        .iter()
+        .zip(&expected)
+        .all(|(a, b)| a.point == b.point && a.index == b.index));
+}
+
+#[test]
+fn test_gen_nodes_hex_spacing() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_hex(&params);
+    assert!(nodes.len() > 100);
+    assert!(min_distance(&nodes) >= params.min_spacing * 0.99);
+    assert!(nodes
+        .iter()
+        .all(|n| n.point.length() <= params.radius - params.margin()));
+}
+
+#[test]
+fn test_gen_nodes_poisson_spacing() {
+    use rand::SeedableRng;
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+    let nodes = gen_nodes_poisson(&mut rng, &params, 30);
+    assert!(nodes.len() > 50);
+    assert!(min_distance(&nodes) > params.min_spacing);
+    assert!(nodes
+        .iter()
+        .all(|n| n.point.length() <= params.node_radius()));
+}
+
+#[test]
+fn test_gen_nodes_poisson_no_room() {
+    use rand::SeedableRng;
+    // The hole leaves a ring narrower than the margin, which admits nothing.
+    let params = Params {
+        radius: 100.0,
+        inner_radius: 99.0,
+        ..Default::default()
+    };
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+    assert!(gen_nodes_poisson(&mut rng, &params, 30).is_empty());
+}
+
+#[test]
+fn test_gen_nodes_rings_spacing() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    for num_rings in [3, 6, 12] {
+        let nodes = gen_nodes_rings(&params, num_rings);
+        assert!(min_distance(&nodes) >= params.min_spacing * 0.99);
+        let outermost = nodes.iter().map(|n| n.point.length()).fold(0.0, f64::max);
+        assert!(outermost <= params.node_radius() + 1e-9);
+    }
+}
+
+#[test]
+fn test_radical_inverse() {
+    let base2: Vec<f64> = (1..=4).map(|i| radical_inverse(i, 2)).collect();
+    assert_eq!(base2, vec![0.5, 0.25, 0.75, 0.125]);
+    let base3: Vec<f64> = (1..=3).map(|i| radical_inverse(i, 3)).collect();
+    assert_eq!(base3, vec![1.0 / 3.0, 2.0 / 3.0, 1.0 / 9.0]);
+}
+
+#[test]
+fn test_gen_nodes_fibonacci() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = gen_nodes_fibonacci(&params);
+    assert!(nodes.len() > 50);
+    assert!(min_distance(&nodes) > params.min_spacing);
+    assert!(nodes
+        .iter()
+        .all(|n| n.point.length() <= params.node_radius() + 1e-9));
+}
+
+#[test]
+fn test_gen_nodes_rect() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let margin = params.margin();
+    for nodes in [
+        gen_nodes_rect(&params, 120.0, 60.0),
+        gen_nodes_rect_grid(&params, 120.0, 60.0),
+    ] {
+        assert!(nodes.len() > 50);
+        assert!(min_distance(&nodes) >= params.min_spacing);
+        assert!(nodes
+            .iter()
+            .all(|n| n.point.x.abs() <= 60.0 - margin && n.point.y.abs() <= 30.0 - margin));
+    }
+    // The same nodes as a grid layout inside --shape rect.
+    let rect = Params {
+        boundary: Some(BoundaryShape::Rectangle {
+            width: 120.0,
+            height: 60.0,
+        }),
+        ..params.clone()
+    };
+    let points = |nodes: Vec<Node>| nodes.into_iter().map(|n| n.point).collect::<Vec<_>>();
+    assert_eq!(
+        points(gen_nodes_rect(&params, 120.0, 60.0)),
+        points(gen_nodes_grid(&rect))
+    );
+}
+
+#[test]
+fn test_symmetrize_nodes() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let nodes = symmetrize_nodes(&params, &gen_nodes_grid(&params), 5);
+    assert_eq!(nodes.len() % 5, 0);
+    assert!(min_distance(&nodes) > params.min_spacing);
+    let m = nodes.len() / 5;
+    for node in &nodes {
+        let rotated = node.point.rotate(TAU / 5.0);
+        let image = nodes[(node.index + m) % nodes.len()].point;
+        assert!((rotated - image).length() < 1e-9);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::*;
+use petgraph::graph::{NodeIndex, UnGraph};
+use std::collections::HashSet;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NodeData {
+    pub point: V2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct EdgeData {
+    pub length: f64,
+}
+
+pub type PetMaze = UnGraph<NodeData, EdgeData>;
+
+// Graph node `i` is maze node `i`.
+pub fn to_petgraph(maze: &Maze) -> PetMaze {
+    let mut graph = PetMaze::with_capacity(maze.nodes.len(), maze.edges.len());
+    for node in &maze.nodes {
+        graph.add_node(NodeData { point: node.point });
+    }
+    for Edge(a, b) in maze.sorted_edges() {
+        let length = (maze.nodes[a].point - maze.nodes[b].point).length();
+        graph.add_edge(NodeIndex::new(a), NodeIndex::new(b), EdgeData { length });
+    }
+    graph
+}
+
+// A maze with the graph's nodes and edges, a circular boundary just big
+// enough to hold them, and the ends of its longest path as the start and
+// end. Edge lengths are taken from the node positions.
+pub fn from_petgraph(graph: &PetMaze) -> Maze {
+    let nodes: Vec<Node> = graph
+        .node_indices()
+        .map(|i| Node {
+            point: graph[i].point,
+            index: i.index(),
+        })
+        .collect();
+    let edges: HashSet<Edge> = graph
+        .edge_indices()
+        .filter_map(|e| graph.edge_endpoints(e))
+        .map(|(a, b)| Edge(a.index(), b.index()))
+        .collect();
+    let (_, start, end) = compute_diameter(&MazeGraph::from_edges(&edges, nodes.len()));
+    let params = Params {
+        radius: nodes.iter().map(|n| n.point.length()).fold(0.0, f64::max),
+        ..Default::default()
+    };
+    Maze {
+        depths: edge_depths(&edges, nodes.len(), start, Default::default()),
+        metadata: MazeMeta {
+            radius: params.radius,
+            tube_radius: params.tube_radius,
+            seed: None,
+            algorithm: None,
+            layout: None,
+            min_spacing: None,
+            node_count: nodes.len(),
+        },
+        nodes,
+        edges,
+        start,
+        end,
+        params,
+    }
+}
+
+#[test]
+fn test_petgraph_round_trip() {
+    let builder = MazeBuilder::new().radius(60.0).seed(3);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let graph = to_petgraph(&maze);
+    assert_eq!(graph.node_count(), maze.nodes.len());
+    assert_eq!(graph.edge_count(), maze.edges.len());
+    let back = from_petgraph(&graph);
+    for (a, b) in back.nodes.iter().zip(&maze.nodes) {
+        assert_eq!((a.point, a.index), (b.point, b.index));
+    }
+    assert_eq!(back.edges, maze.edges);
+    // Without braiding there are no cycles, so every passage is a bridge.
+    assert_eq!(petgraph::algo::bridges(&graph).count(), maze.edges.len());
+}
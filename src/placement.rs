@@ -0,0 +1,184 @@
+use crate::*;
+use rand::Rng;
+use std::collections::HashSet;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+use std::str::FromStr;
+
+// Where to put the start or end of the maze. Angles are in SVG coordinates,
+// so they turn from the positive x axis toward the bottom of the image.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Placement {
+    Fixed { angle_radians: f64 },
+    // A random angle, or for the end a random reachable node.
+    Random,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    // The start farthest from the centroid of the nodes; the end farthest
+    // from the start in hops.
+    Farthest,
+}
+
+pub type StartPlacement = Placement;
+pub type EndPlacement = Placement;
+
+impl Placement {
+    fn angle(&self, rng: &mut impl Rng) -> Option<f64> {
+        match *self {
+            Placement::Fixed { angle_radians } => Some(angle_radians),
+            Placement::Random => Some(rng.gen_range(0.0..TAU)),
+            Placement::Top => Some(-FRAC_PI_2),
+            Placement::Bottom => Some(FRAC_PI_2),
+            Placement::Left => Some(PI),
+            Placement::Right => Some(0.0),
+            Placement::Farthest => None,
+        }
+    }
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    // Parses `top`, `bottom`, `left`, `right`, `random`, `farthest` or
+    // `angle:<radians>`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "top" => Ok(Placement::Top),
+            "bottom" => Ok(Placement::Bottom),
+            "left" => Ok(Placement::Left),
+            "right" => Ok(Placement::Right),
+            "random" => Ok(Placement::Random),
+            "farthest" => Ok(Placement::Farthest),
+            _ => {
+                let angle_radians = s
+                    .strip_prefix("angle:")
+                    .and_then(|a| a.parse::<f64>().ok())
+                    .filter(|a| a.is_finite())
+                    .ok_or_else(|| {
+                        format!(
+                            "expected top, bottom, left, right, random, farthest or \
+                             angle:<radians>, got {s:?}"
+                        )
+                    })?;
+                Ok(Placement::Fixed { angle_radians })
+            }
+        }
+    }
+}
+
+// Of `candidates`, the one nearest a point just outside all the nodes in the
+// direction `angle` from the center.
+fn outermost_toward(nodes: &[Node], candidates: &[Index], angle: f64) -> Index {
+    let extent = nodes.iter().map(|n| n.point.length()).fold(0.0, f64::max);
+    let target: V2 = Pol {
+        a: angle,
+        r: extent * 1.1 + 1.0,
+    }
+    .into();
+    *candidates
+        .iter()
+        .min_by(|&&a, &&b| {
+            let a = (nodes[a].point - target).length();
+            let b = (nodes[b].point - target).length();
+            a.total_cmp(&b)
+        })
+        .unwrap()
+}
+
+// `nodes` must not be empty.
+pub fn place_start_node(nodes: &[Node], strategy: StartPlacement, rng: &mut impl Rng) -> Node {
+    let all: Vec<Index> = (0..nodes.len()).collect();
+    match strategy.angle(rng) {
+        Some(angle) => nodes[outermost_toward(nodes, &all, angle)],
+        None => {
+            let centroid = nodes
+                .iter()
+                .fold(V2 { x: 0.0, y: 0.0 }, |sum, n| sum + n.point)
+                / nodes.len() as f64;
+            *nodes
+                .iter()
+                .max_by(|a, b| {
+                    let a = (a.point - centroid).length();
+                    let b = (b.point - centroid).length();
+                    a.total_cmp(&b)
+                })
+                .unwrap()
+        }
+    }
+}
+
+// Picks the end among the nodes reachable from `start` along `edges`.
+pub fn place_end_node(
+    nodes: &[Node],
+    edges: &HashSet<Edge>,
+    start: Index,
+    strategy: EndPlacement,
+    rng: &mut impl Rng,
+) -> Index {
+    let hops = MazeGraph::from_edges(edges, nodes.len()).hops_from(start);
+    let reachable: Vec<Index> = (0..nodes.len()).filter(|&i| hops[i].is_some()).collect();
+    match strategy {
+        Placement::Farthest => (0..nodes.len()).max_by_key(|&i| hops[i]).unwrap_or(start),
+        Placement::Random if reachable.len() > 1 => {
+            let others: Vec<Index> = reachable.into_iter().filter(|&i| i != start).collect();
+            others[rng.gen_range(0..others.len())]
+        }
+        Placement::Random => start,
+        _ => outermost_toward(nodes, &reachable, strategy.angle(rng).unwrap()),
+    }
+}
+
+#[test]
+fn test_parse_placement() {
+    assert_eq!("top".parse(), Ok(Placement::Top));
+    assert_eq!("farthest".parse(), Ok(Placement::Farthest));
+    assert_eq!(
+        "angle:1.5".parse(),
+        Ok(Placement::Fixed { angle_radians: 1.5 })
+    );
+    assert!("angle:".parse::<Placement>().is_err());
+    assert!("north".parse::<Placement>().is_err());
+}
+
+#[test]
+fn test_place_start_and_end() {
+    use rand::SeedableRng;
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+    let point = |x, y| V2 { x, y };
+    // A plus sign: 0 in the middle, then right, bottom, left and top arms,
+    // with the left arm one node longer.
+    let nodes: Vec<Node> = [
+        point(0.0, 0.0),
+        point(1.0, 0.0),
+        point(0.0, 1.0),
+        point(-1.0, 0.0),
+        point(0.0, -1.0),
+        point(-2.0, 0.0),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, point)| Node { point, index })
+    .collect();
+    let place =
+        |strategy, rng: &mut rand_chacha::ChaCha8Rng| place_start_node(&nodes, strategy, rng).index;
+    assert_eq!(place(Placement::Right, &mut rng), 1);
+    assert_eq!(place(Placement::Bottom, &mut rng), 2);
+    assert_eq!(place(Placement::Left, &mut rng), 5);
+    assert_eq!(place(Placement::Top, &mut rng), 4);
+    assert_eq!(place(Placement::Fixed { angle_radians: 0.0 }, &mut rng), 1);
+    assert_eq!(place(Placement::Farthest, &mut rng), 5);
+
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(0, 2), Edge(0, 3), Edge(3, 5)].into();
+    let end = |strategy, rng: &mut rand_chacha::ChaCha8Rng| {
+        place_end_node(&nodes, &edges, 1, strategy, rng)
+    };
+    assert_eq!(end(Placement::Farthest, &mut rng), 5);
+    // 4 isn't joined to the rest, so the nearest reachable node wins.
+    assert_eq!(end(Placement::Top, &mut rng), 0);
+    assert_eq!(end(Placement::Bottom, &mut rng), 2);
+    for _ in 0..10 {
+        let random = end(Placement::Random, &mut rng);
+        assert!([0, 2, 3, 5].contains(&random));
+    }
+}
@@ -0,0 +1,811 @@
+use crate::*;
+use hex_color::HexColor;
+use rand::Rng;
+use std::collections::HashSet;
+use std::time::{SystemTime, UNIX_EPOCH};
+use svg::node::element::path::Data;
+use svg::node::element::{
+    Circle, ClipPath, Definitions, Description, Element, Group, Line, Path, Rectangle, Style, Title,
+};
+use svg::node::Text;
+use svg::Document;
+use svg::Node;
+
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub draw_factor: f64,
+    pub background_color: String,
+    pub boundary_color: String,
+    pub path_color: String,
+    pub start_color: String,
+    pub end_color: String,
+    pub solution_color: String,
+    pub color_by: ColorBy,
+    // How far passages bow out, as a fraction of their length; 0 draws them
+    // straight.
+    pub curvature: f64,
+    // Draw each passage as a filled outline that narrows toward nodes with
+    // fewer passages, instead of a stroke. Overrides `curvature`.
+    pub taper: bool,
+    // Seconds over which the solution is drawn in from start to end; None
+    // shows it all at once.
+    pub animate_solve: Option<f64>,
+    // The ends of the gradient used by `ColorBy::Depth`.
+    pub color_start: HexColor,
+    pub color_end: HexColor,
+    // Draw the walls rather than the passages: the boundary is filled with
+    // `boundary_color` and each passage is cut out of it as a rectangle in
+    // `background_color`. Overrides `color_by`, `curvature` and `taper`.
+    pub walls: bool,
+    // Fill each node's Voronoi region behind the passages, colored by its
+    // distance from the start along the `color_start`-`color_end` gradient.
+    pub voronoi: bool,
+    // Record how the maze was made in the annotations layer; see
+    // `svg_metadata`.
+    pub metadata: bool,
+    // Draw a scale bar in the annotations layer; see `scale_bar`.
+    pub scale_bar: Option<ScaleBar>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScaleBar {
+    // How many maze units make one `unit`.
+    pub pixels_per_unit: f64,
+    pub unit: String,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        apply_theme(Theme::Classic)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Theme {
+    // White paths on black.
+    #[default]
+    Classic,
+    // White on navy blue.
+    Blueprint,
+    // Dark brown on sepia.
+    Parchment,
+    // Cyan on black.
+    Neon,
+    // Light gray on white.
+    Minimal,
+    // Black paths on white.
+    Inverted,
+}
+
+// Default options with the background, path, marker and solution colors of
+// `theme`.
+pub fn apply_theme(theme: Theme) -> RenderOptions {
+    let (background, path, start, end, solution) = match theme {
+        Theme::Classic => ("#111111", "white", "green", "red", "#FFD700"),
+        Theme::Blueprint => ("#0B2545", "white", "#7CFC00", "#FF6347", "#FFD700"),
+        Theme::Parchment => ("#F5DEB3", "#5C4033", "#2E8B57", "#8B0000", "#B8860B"),
+        Theme::Neon => ("black", "#00FFFF", "#39FF14", "#FF00FF", "#FFFF00"),
+        Theme::Minimal => ("white", "#CCCCCC", "#66BB6A", "#EF5350", "#555555"),
+        Theme::Inverted => ("white", "black", "green", "red", "#1E90FF"),
+    };
+    RenderOptions {
+        draw_factor: 0.9,
+        background_color: background.to_string(),
+        boundary_color: "#444444".to_string(),
+        path_color: path.to_string(),
+        start_color: start.to_string(),
+        end_color: end.to_string(),
+        solution_color: solution.to_string(),
+        color_by: ColorBy::Solid,
+        curvature: 0.0,
+        taper: false,
+        animate_solve: None,
+        color_start: HexColor::rgb(0x00, 0x33, 0x66),
+        color_end: HexColor::rgb(0xFF, 0xFF, 0x00),
+        walls: false,
+        voronoi: false,
+        metadata: false,
+        scale_bar: None,
+    }
+}
+
+// `opts` switched to wall drawing: dark walls on white, as printed mazes are
+// usually drawn.
+pub fn apply_walls(opts: RenderOptions) -> RenderOptions {
+    RenderOptions {
+        background_color: "white".to_string(),
+        boundary_color: "#222222".to_string(),
+        walls: true,
+        ..opts
+    }
+}
+
+pub fn rand_col(rng: &mut impl Rng) -> String {
+    rng.gen::<HexColor>().to_string()
+}
+
+// Every element goes into one of these layers, bottom to top, so editors like
+// Inkscape can toggle them independently.
+pub const LAYERS: [&str; 6] = [
+    "background",
+    "edges",
+    "nodes",
+    "solution",
+    "markers",
+    "annotations",
+];
+
+pub fn render_svg(maze: &Maze, opts: &RenderOptions) -> Document {
+    render_layers(maze, None, opts)
+}
+
+// Like `render_svg`, with `path` highlighted between the passages and the
+// start and end markers.
+pub fn render_svg_solved(maze: &Maze, path: &[Index], opts: &RenderOptions) -> Document {
+    render_layers(maze, Some(path), opts)
+}
+
+pub const METADATA_NAMESPACE: &str = "https://github.com/wbbradley/maze";
+
+// Records how the maze was made in a <metadata> element, as the attributes of
+// a <maze:params> element in METADATA_NAMESPACE. Unknown values are left out.
+// The document has to declare the namespace, as `render_svg` does when
+// `RenderOptions::metadata` is set.
+pub fn svg_metadata(meta: &MazeMeta) -> Element {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut maze_params = Element::new("maze:params");
+    if let Some(seed) = meta.seed {
+        maze_params.assign("seed", seed);
+    }
+    if let Some(algorithm) = meta.algorithm {
+        maze_params.assign("algorithm", value_name(&algorithm));
+    }
+    if let Some(layout) = meta.layout {
+        maze_params.assign("layout", value_name(&layout));
+    }
+    maze_params.assign("radius", meta.radius);
+    maze_params.assign("tube_radius", meta.tube_radius);
+    if let Some(min_spacing) = meta.min_spacing {
+        maze_params.assign("min_spacing", min_spacing);
+    }
+    maze_params.assign("timestamp", timestamp);
+    maze_params.assign("version", env!("CARGO_PKG_VERSION"));
+    let mut metadata = Element::new("metadata");
+    metadata.append(maze_params);
+    metadata
+}
+
+// Adds `svg_metadata` to the document's annotations layer and declares its
+// namespace.
+pub fn add_svg_metadata(document: Document, meta: &MazeMeta) -> Document {
+    add_annotation(
+        document.set("xmlns:maze", METADATA_NAMESPACE),
+        svg_metadata(meta),
+    )
+}
+
+// Puts <title> and <desc> first in the document, where screen readers look
+// for them.
+pub fn add_svg_title(mut document: Document, title: &str, desc: &str) -> Document {
+    let children = document.get_children_mut();
+    children.insert(
+        0,
+        Box::new(Description::new().add(Text::new(escape_xml(desc)))),
+    );
+    children.insert(0, Box::new(Title::new().add(Text::new(escape_xml(title)))));
+    document
+}
+
+// The svg crate writes text nodes as they are.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn default_svg_title(meta: &MazeMeta) -> String {
+    let name = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+    format!(
+        "Maze (algorithm={}, layout={}, seed={})",
+        name(meta.algorithm.map(|a| value_name(&a))),
+        name(meta.layout.map(|l| value_name(&l))),
+        name(meta.seed.map(|s| s.to_string())),
+    )
+}
+
+pub fn default_svg_description(maze: &Maze) -> String {
+    let shape = match *maze.params.boundary() {
+        BoundaryShape::Circle { .. } => "circular",
+        BoundaryShape::Rectangle { .. } => "rectangular",
+        BoundaryShape::Polygon { .. } => "polygonal",
+    };
+    format!(
+        "A {shape} maze with {} nodes and {} edges. Start is marked green, end is marked red.",
+        maze.nodes.len(),
+        maze.edges.len()
+    )
+}
+
+// The longest of 1, 2 or 5 times a power of ten, in physical units, that
+// takes up no more than a quarter of `width` maze units.
+pub fn scale_bar_length(width: f64, pixels_per_unit: f64) -> f64 {
+    let most = width / 4.0 / pixels_per_unit;
+    let power = 10f64.powf(most.log10().floor());
+    [5.0, 2.0, 1.0]
+        .into_iter()
+        .map(|step| step * power)
+        .find(|&length| length <= most)
+        .unwrap_or(power)
+}
+
+// A labeled bar for the bottom right corner of `maze`'s view box, capped by
+// ticks and split by one in the middle.
+pub fn scale_bar(maze: &Maze, scale: &ScaleBar) -> Group {
+    draw_scale_bar(view_box(maze), scale.pixels_per_unit, &scale.unit)
+}
+
+// Adds `scale_bar` to the document's annotations layer, reading the corner
+// from its viewBox. `pixels_per_unit` is how many maze units make one
+// `unit_label`.
+pub fn add_scale_bar(document: Document, pixels_per_unit: f64, unit_label: &str) -> Document {
+    let view_box: Vec<f64> = document
+        .get_attributes()
+        .get("viewBox")
+        .map(|v| {
+            v.split_whitespace()
+                .filter_map(|n| n.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let [x, y, width, height] = view_box[..] else {
+        return document;
+    };
+    let bar = draw_scale_bar((x, y, width, height), pixels_per_unit, unit_label);
+    add_annotation(document, bar)
+}
+
+fn draw_scale_bar(
+    (x, y, width, height): (f64, f64, f64, f64),
+    pixels_per_unit: f64,
+    unit_label: &str,
+) -> Group {
+    let length = scale_bar_length(width, pixels_per_unit);
+    let (bar, margin, tick) = (length * pixels_per_unit, width * 0.03, width * 0.01);
+    let (right, bottom) = (x + width - margin, y + height - margin);
+    let color = "#888888";
+    let line = |x1: f64, y1: f64, x2: f64, y2: f64| {
+        Line::new()
+            .set("x1", x1)
+            .set("y1", y1)
+            .set("x2", x2)
+            .set("y2", y2)
+            .set("stroke", color)
+            .set("stroke-width", width * 0.002)
+    };
+    let mut group =
+        Group::new()
+            .set("id", "scale-bar")
+            .add(line(right - bar, bottom, right, bottom));
+    for tick_x in [right - bar, right - bar / 2.0, right] {
+        group = group.add(line(tick_x, bottom - tick, tick_x, bottom));
+    }
+    let label = svg::node::element::Text::new()
+        .add(Text::new(escape_xml(&format!("{length} {unit_label}"))))
+        .set("x", right - bar / 2.0)
+        .set("y", bottom - tick * 1.5)
+        .set("text-anchor", "middle")
+        .set("font-family", "sans-serif")
+        .set("font-size", width * 0.02)
+        .set("fill", color);
+    group.add(label)
+}
+
+// Appends `node` to the <g id="annotations"> layer that `render_svg` ends
+// the document with, or to the root when there is none. The svg crate can't
+// reach into a child element, so the layer is rebuilt around what it held.
+fn add_annotation(mut document: Document, node: impl Into<Box<dyn Node>>) -> Document {
+    let children = document.get_children_mut();
+    let printed = children.last().map(ToString::to_string).unwrap_or_default();
+    let held = if printed == "<g id=\"annotations\"/>" {
+        Some("")
+    } else {
+        printed
+            .strip_prefix("<g id=\"annotations\">\n")
+            .and_then(|rest| rest.strip_suffix("\n</g>"))
+    };
+    let Some(held) = held else {
+        return document.add(node);
+    };
+    let mut layer = Group::new().set("id", "annotations");
+    if !held.is_empty() {
+        layer = layer.add(Text::new(held));
+    }
+    *children.last_mut().unwrap() = Box::new(layer.add(node));
+    document
+}
+
+// The boundary's bounding box with 0.5% padding on each side, as
+// (x, y, width, height).
+fn view_box(maze: &Maze) -> (f64, f64, f64, f64) {
+    let (top_left, bottom_right) = maze.params.boundary().bounds();
+    let size = bottom_right - top_left;
+    (
+        top_left.x - size.x * 0.005,
+        top_left.y - size.y * 0.005,
+        size.x * 1.01,
+        size.y * 1.01,
+    )
+}
+
+fn render_layers(maze: &Maze, path: Option<&[Index]>, opts: &RenderOptions) -> Document {
+    let mut document = Document::new().set("viewBox", view_box(maze)).set(
+        "style",
+        format!("background-color: {}", opts.background_color).as_str(),
+    );
+    let mut annotations = Group::new().set("id", "annotations");
+    if opts.metadata {
+        document = document.set("xmlns:maze", METADATA_NAMESPACE);
+        annotations = annotations.add(svg_metadata(&maze.metadata));
+    }
+    if let Some(scale) = &opts.scale_bar {
+        annotations = annotations.add(scale_bar(maze, scale));
+    }
+    let (edges, nodes) = passage_layers(maze, opts);
+    // Passages and caps that overshoot the outline are cut off at it.
+    let clip = ClipPath::new()
+        .set("id", "maze-boundary")
+        .add(boundary_outline(&maze.params.boundary()));
+    let clipped = Group::new()
+        .set("clip-path", "url(#maze-boundary)")
+        .add(edges)
+        .add(nodes);
+    let solution = match path {
+        Some(path) => solution_group(maze, path, opts),
+        None => Group::new().set("id", "solution"),
+    };
+    document
+        .add(Definitions::new().add(clip))
+        .add(background_layer(maze, opts))
+        .add(clipped)
+        .add(solution)
+        .add(markers_layer(maze, opts))
+        .add(annotations)
+}
+
+fn background_layer(maze: &Maze, opts: &RenderOptions) -> Group {
+    let params = &maze.params;
+    // Painted as well as set as the CSS background, which some renderers
+    // ignore.
+    let (x, y, width, height) = view_box(maze);
+    let backdrop = Rectangle::new()
+        .set("x", x)
+        .set("y", y)
+        .set("width", width)
+        .set("height", height)
+        .set("fill", opts.background_color.as_str());
+    let mut outline = boundary_outline(&params.boundary());
+    outline.assign("fill", opts.boundary_color.as_str());
+    let mut group = Group::new()
+        .set("id", "background")
+        .add(backdrop)
+        .add(outline);
+    if opts.voronoi {
+        group = group.add(voronoi_group(maze, opts));
+    }
+    if params.inner_radius > 0.0 {
+        group = group.add(
+            Circle::new()
+                .set("r", params.inner_radius)
+                .set("cx", 0.0)
+                .set("cy", 0.0)
+                .set("fill", opts.background_color.as_str()),
+        );
+    }
+    group
+}
+
+// Each node's region, leaving out the ones the start can't reach.
+fn voronoi_group(maze: &Maze, opts: &RenderOptions) -> Group {
+    let distances = maze.distances();
+    let max_distance = distances
+        .iter()
+        .flatten()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1);
+    let cells = voronoi_cells(&maze.nodes, &maze.params.boundary());
+    let mut group = Group::new().set("id", "voronoi");
+    for (cell, distance) in cells.iter().zip(&distances) {
+        if let Some(distance) = distance {
+            let t = *distance as f64 / max_distance as f64;
+            group = group.add(
+                Path::new()
+                    .set(
+                        "fill",
+                        lerp_color(opts.color_start, opts.color_end, t).to_string(),
+                    )
+                    .set("d", polygon_data(cell)),
+            );
+        }
+    }
+    group
+}
+
+// The shape of the boundary, without any styling.
+fn boundary_outline(boundary: &BoundaryShape) -> Element {
+    match boundary {
+        BoundaryShape::Circle { radius } => Circle::new()
+            .set("r", *radius)
+            .set("cx", 0.0)
+            .set("cy", 0.0)
+            .into(),
+        BoundaryShape::Rectangle { width, height } => Rectangle::new()
+            .set("x", -width / 2.0)
+            .set("y", -height / 2.0)
+            .set("width", *width)
+            .set("height", *height)
+            .into(),
+        BoundaryShape::Polygon { vertices } => {
+            let data = vertices
+                .iter()
+                .skip(1)
+                .fold(
+                    Data::new().move_to((vertices[0].x, vertices[0].y)),
+                    |data, v| data.line_to((v.x, v.y)),
+                )
+                .close();
+            Path::new().set("d", data).into()
+        }
+    }
+}
+
+// The passages, and a round cap on each node they touch.
+fn passage_layers(maze: &Maze, opts: &RenderOptions) -> (Group, Group) {
+    let nodes = &maze.nodes;
+    let tube_radius = maze.params.tube_radius;
+    let path_color = opts.path_color.as_str();
+    let stroke_width = tube_radius * opts.draw_factor * 2.0;
+    let mut edge_group = Group::new().set("id", "edges");
+    let mut node_group = Group::new().set("id", "nodes");
+    let mut drawn_nodes: HashSet<Index> = HashSet::new();
+
+    let max_depth = maze.depths.values().max().copied().unwrap_or(0).max(1);
+    let distances = maze.distances();
+    let max_distance = distances
+        .iter()
+        .flatten()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1);
+    let edge_color = |edge @ Edge(a, b): Edge| match opts.color_by {
+        ColorBy::Solid => path_color.to_string(),
+        ColorBy::Depth => {
+            let depth = maze.depths.get(&edge).copied().unwrap_or(0);
+            let t = depth as f64 / max_depth as f64;
+            lerp_color(opts.color_start, opts.color_end, t).to_string()
+        }
+        ColorBy::Distance => {
+            let distance = |i: Index| distances[i].unwrap_or(0) as f64;
+            let t = (distance(a) + distance(b)) / 2.0 / max_distance as f64;
+            heatmap_color(t).to_string()
+        }
+        ColorBy::Angle => angle_to_color((nodes[b].point - nodes[a].point).angle()).to_string(),
+    };
+
+    let mut degree = vec![0; nodes.len()];
+    for &Edge(a, b) in &maze.edges {
+        degree[a] += 1;
+        degree[b] += 1;
+    }
+    let max_degree = degree.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let tapered_radius = |i: Index| tube_radius * opts.draw_factor * degree[i] as f64 / max_degree;
+
+    // Passages are widened by half the spacing so that the walls between
+    // them don't look heavier than the passages themselves.
+    let wall_gap = tube_radius + maze.params.min_spacing * 0.25;
+
+    // Draw in a stable order so that equal mazes produce identical documents.
+    for edge @ Edge(a, b) in maze.sorted_edges() {
+        if opts.walls {
+            let fill = opts.background_color.as_str();
+            let data = rectangle_path_data(nodes[a].point, nodes[b].point, wall_gap);
+            edge_group = edge_group.add(Path::new().set("fill", fill).set("d", data));
+            for i in [a, b] {
+                if drawn_nodes.insert(i) {
+                    node_group = node_group.add(
+                        Circle::new()
+                            .set("r", wall_gap)
+                            .set("cx", nodes[i].point.x)
+                            .set("cy", nodes[i].point.y)
+                            .set("fill", fill),
+                    );
+                }
+            }
+            continue;
+        }
+        let color = edge_color(edge);
+        if opts.taper {
+            // The rounded ends already cover the nodes.
+            let data = tapered_path_data(
+                nodes[a].point,
+                nodes[b].point,
+                tapered_radius(a),
+                tapered_radius(b),
+            );
+            edge_group = edge_group.add(Path::new().set("fill", color.as_str()).set("d", data));
+            continue;
+        }
+        edge_group = edge_group.add(passage_path(maze, a, b, &color, stroke_width, opts));
+        for i in [a, b] {
+            if drawn_nodes.insert(i) {
+                node_group = node_group.add(
+                    Circle::new()
+                        .set("r", tube_radius * opts.draw_factor)
+                        .set("cx", nodes[i].point.x)
+                        .set("cy", nodes[i].point.y)
+                        .set("fill", color.as_str()),
+                );
+            }
+        }
+    }
+    (edge_group, node_group)
+}
+
+fn markers_layer(maze: &Maze, opts: &RenderOptions) -> Group {
+    let marker = |index: Index, color: &str| {
+        Circle::new()
+            .set("r", maze.params.tube_radius * 1.75)
+            .set("cx", maze.nodes[index].point.x)
+            .set("cy", maze.nodes[index].point.y)
+            .set("fill", color)
+    };
+    Group::new()
+        .set("id", "markers")
+        .add(marker(maze.start, &opts.start_color))
+        .add(marker(maze.end, &opts.end_color))
+}
+
+// Draws `path`, a sequence of node indices, as the "solution" layer.
+// When animated, each segment is dashed to its own length and the dash slides
+// in once the segments before it are done, at a constant speed overall.
+pub fn solution_group(maze: &Maze, path: &[Index], opts: &RenderOptions) -> Group {
+    let stroke_width = maze.params.tube_radius * opts.draw_factor * 2.5;
+    let mut group = Group::new().set("id", "solution");
+    let lengths: Vec<f64> = path
+        .windows(2)
+        .map(|pair| passage_length(maze, pair[0], pair[1], opts))
+        .collect();
+    let total: f64 = lengths.iter().sum();
+    if opts.animate_solve.is_some() {
+        group = group.add(Style::new(
+            "@keyframes reveal { to { stroke-dashoffset: 0; } }",
+        ));
+    }
+    let mut drawn = 0.0;
+    for (pair, length) in path.windows(2).zip(lengths) {
+        let mut segment = passage_path(
+            maze,
+            pair[0],
+            pair[1],
+            &opts.solution_color,
+            stroke_width,
+            opts,
+        );
+        if let Some(duration) = opts.animate_solve.filter(|_| total > 0.0) {
+            let seconds = |l: f64| l / total * duration;
+            segment = segment
+                .set("stroke-dasharray", length)
+                .set("stroke-dashoffset", length)
+                .set(
+                    "style",
+                    format!(
+                        "animation: reveal {}s linear {}s forwards",
+                        seconds(length),
+                        seconds(drawn)
+                    ),
+                );
+        }
+        drawn += length;
+        group = group.add(segment);
+    }
+    group
+}
+
+pub fn add_edge(
+    document: Document,
+    start: V2,
+    end: V2,
+    color: &str,
+    stroke_width: f64,
+) -> Document {
+    // eprintln!("[add_edge] start={start:?} end={end:?}");
+    document.add(edge_path(start, end, color, stroke_width))
+}
+
+// The passage between nodes `a` and `b`, bowed by `opts.curvature`. Each
+// passage bows by its own amount and side, fixed by a hash of its ends, and
+// the same way whichever end it is drawn from.
+fn passage_path(
+    maze: &Maze,
+    a: Index,
+    b: Index,
+    color: &str,
+    stroke_width: f64,
+    opts: &RenderOptions,
+) -> Path {
+    let (start, end) = (maze.nodes[a].point, maze.nodes[b].point);
+    match passage_control(maze, a, b, opts) {
+        Some(control) => curved_edge_path(start, control, end, color, stroke_width),
+        None => edge_path(start, end, color, stroke_width),
+    }
+}
+
+// The length of the passage drawn by `passage_path`.
+fn passage_length(maze: &Maze, a: Index, b: Index, opts: &RenderOptions) -> f64 {
+    let (start, end) = (maze.nodes[a].point, maze.nodes[b].point);
+    match passage_control(maze, a, b, opts) {
+        Some(control) => quadratic_length(start, control, end),
+        None => (end - start).length(),
+    }
+}
+
+// The control point of the curve between `a` and `b`, or None if they are
+// joined by a straight line.
+fn passage_control(maze: &Maze, a: Index, b: Index, opts: &RenderOptions) -> Option<V2> {
+    if opts.curvature == 0.0 {
+        return None;
+    }
+    let (p, q) = (a.min(b), a.max(b));
+    let hash = (p as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (q as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    // Uniform in [-1, 1).
+    let bow = (hash >> 11) as f64 / (1u64 << 52) as f64 - 1.0;
+    let (p, q) = (maze.nodes[p].point, maze.nodes[q].point);
+    Some(compute_control_point(p, q, opts.curvature * bow))
+}
+
+// Arc length of a quadratic Bezier curve, by Simpson's rule over its speed.
+pub fn quadratic_length(start: V2, control: V2, end: V2) -> f64 {
+    const STEPS: usize = 32;
+    let speed =
+        |t: f64| ((control - start) * (2.0 * (1.0 - t)) + (end - control) * (2.0 * t)).length();
+    let h = 1.0 / STEPS as f64;
+    let sum: f64 = (0..=STEPS)
+        .map(|i| {
+            let weight = if i == 0 || i == STEPS {
+                1.0
+            } else if i % 2 == 1 {
+                4.0
+            } else {
+                2.0
+            };
+            weight * speed(i as f64 * h)
+        })
+        .sum();
+    sum * h / 3.0
+}
+
+// Outline of a passage `r_a` wide at `a` and `r_b` wide at `b`: two sides
+// joined by a half circle around each end.
+pub fn tapered_path_data(a: V2, b: V2, r_a: f64, r_b: f64) -> Data {
+    let along = (b - a).normalise();
+    let normal = V2 {
+        x: -along.y,
+        y: along.x,
+    };
+    let (a1, a2) = (a + normal * r_a, a - normal * r_a);
+    let (b1, b2) = (b + normal * r_b, b - normal * r_b);
+    Data::new()
+        .move_to((a1.x, a1.y))
+        .line_to((b1.x, b1.y))
+        .elliptical_arc_to((r_b, r_b, 0, 0, 0, b2.x, b2.y))
+        .line_to((a2.x, a2.y))
+        .elliptical_arc_to((r_a, r_a, 0, 0, 0, a1.x, a1.y))
+        .close()
+}
+
+// Outline of the rectangle `2 * half_width` wide running from `a` to `b`.
+pub fn rectangle_path_data(a: V2, b: V2, half_width: f64) -> Data {
+    let along = (b - a).normalise();
+    let normal = V2 {
+        x: -along.y,
+        y: along.x,
+    } * half_width;
+    let corners = [a + normal, b + normal, b - normal, a - normal];
+    Data::new()
+        .move_to((corners[0].x, corners[0].y))
+        .line_to((corners[1].x, corners[1].y))
+        .line_to((corners[2].x, corners[2].y))
+        .line_to((corners[3].x, corners[3].y))
+        .close()
+}
+
+// The midpoint of start-end pushed sideways by `curvature` times the length;
+// negative values push it to the other side.
+pub fn compute_control_point(start: V2, end: V2, curvature: f64) -> V2 {
+    let along = end - start;
+    let normal = V2 {
+        x: -along.y,
+        y: along.x,
+    };
+    (start + end) * 0.5 + normal * curvature
+}
+
+pub fn curved_edge_path(start: V2, control: V2, end: V2, color: &str, stroke_width: f64) -> Path {
+    let data = Data::new()
+        .move_to((start.x, start.y))
+        .quadratic_curve_to((control.x, control.y, end.x, end.y));
+    Path::new()
+        .set("fill", "none")
+        .set("stroke", color)
+        .set("stroke-width", stroke_width)
+        .set("d", data)
+}
+
+pub fn edge_path(start: V2, end: V2, color: &str, stroke_width: f64) -> Path {
+    let data = Data::new()
+        .move_to((start.x, start.y))
+        .line_to((end.x, end.y));
+    Path::new()
+        .set("fill", color)
+        .set("stroke", color)
+        .set("stroke-width", stroke_width)
+        .set("d", data)
+}
+
+// Rasterizes the maze onto a `width` x `height` character grid covering the
+// maze's extents.
+pub fn render_ascii(maze: &Maze, width: usize, height: usize) -> String {
+    let mut grid = vec![vec!['.'; width]; height];
+    let (top_left, bottom_right) = maze.params.boundary().bounds();
+    let size = bottom_right - top_left;
+    let cell = |p: V2| {
+        (
+            (p.x - top_left.x) / size.x * width.saturating_sub(1) as f64,
+            (p.y - top_left.y) / size.y * height.saturating_sub(1) as f64,
+        )
+    };
+    let plot = |grid: &mut [Vec<char>], x: f64, y: f64, c: char| {
+        let (x, y) = (x.round(), y.round());
+        if x >= 0.0 && y >= 0.0 && (x as usize) < width && (y as usize) < height {
+            grid[y as usize][x as usize] = c;
+        }
+    };
+
+    for &Edge(a, b) in &maze.edges {
+        let (x0, y0) = cell(maze.nodes[a].point);
+        let (x1, y1) = cell(maze.nodes[b].point);
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        // Rows point down, so a line falling to the right is '\'.
+        let c = if dy.abs() * 2.0 < dx.abs() {
+            '-'
+        } else if dx.abs() * 2.0 < dy.abs() {
+            '|'
+        } else if dx * dy > 0.0 {
+            '\\'
+        } else {
+            '/'
+        };
+        let steps = dx.abs().max(dy.abs()).ceil() as usize;
+        for i in 1..steps {
+            let t = i as f64 / steps as f64;
+            plot(&mut grid, x0 + dx * t, y0 + dy * t, c);
+        }
+    }
+    for node in &maze.nodes {
+        let (x, y) = cell(node.point);
+        plot(&mut grid, x, y, 'O');
+    }
+
+    let mut out = String::with_capacity((width + 1) * height);
+    for row in grid {
+        out.extend(row);
+        out.push('\n');
+    }
+    out
+}
@@ -1,20 +1,61 @@
-use crate::V2;
+use crate::{V2Ext, V2};
 use std::f64::consts::TAU;
 
-fn cross(a: V2, b: V2) -> f64 {
+// The z component of the 3D cross product: positive when b is
+// counterclockwise from a (clockwise on screen, as SVG's y axis points down),
+// negative when clockwise, zero when parallel. Its size is the area of the
+// parallelogram a and b span.
+pub(crate) fn cross(a: V2, b: V2) -> f64 {
     a.x * b.y - a.y * b.x
 }
 
-fn orient(a: V2, b: V2, c: V2) -> f64 {
+// Which side of the line a->b the point c is on: positive to the left,
+// negative to the right, zero when the three are collinear. Twice the signed
+// area of the triangle abc.
+pub(crate) fn orient(a: V2, b: V2, c: V2) -> f64 {
     cross(b - a, c - a)
 }
 
-fn shrink((a, b): (V2, V2), scale: f64) -> (V2, V2) {
+// Scales ab about its midpoint; 1.0 leaves it as is and 0.0 collapses it.
+pub(crate) fn shrink((a, b): (V2, V2), scale: f64) -> (V2, V2) {
     let mid = (a + b) * 0.5;
     (V2::lerp(mid, a, scale), V2::lerp(mid, b, scale))
 }
 
-pub(crate) fn intersection_with_width(
+// Whether the bounding boxes of segments ab and cd, each grown by `margin`,
+// overlap.
+pub fn aabb_overlap(a: V2, b: V2, c: V2, d: V2, margin: f64) -> bool {
+    a.x.min(b.x) - margin <= c.x.max(d.x) + margin
+        && c.x.min(d.x) - margin <= a.x.max(b.x) + margin
+        && a.y.min(b.y) - margin <= c.y.max(d.y) + margin
+        && c.y.min(d.y) - margin <= a.y.max(b.y) + margin
+}
+
+// Whether tubes of radius `width` around ab and cd overlap.
+pub fn intersection_with_width(a: V2, b: V2, c: V2, d: V2, width: f64) -> bool {
+    if !aabb_overlap(a, b, c, d, width) {
+        return false;
+    }
+    // Rounding in the distance can differ with the order near the threshold,
+    // so always test the pair the same way round.
+    if (c.x, c.y, d.x, d.y) < (a.x, a.y, b.x, b.y) {
+        return intersection_with_width(c, d, a, b, width);
+    }
+    // Passages leaving the same node always touch there, so they only collide
+    // if the far end of one runs alongside the other.
+    let shared = [(a, b, c, d), (a, b, d, c), (b, a, c, d), (b, a, d, c)]
+        .into_iter()
+        .find(|&(p, _, q, _)| p == q);
+    if let Some((_, p, _, q)) = shared {
+        return segments_min_distance(p, p, c, d) < 2.0 * width
+            || segments_min_distance(q, q, a, b) < 2.0 * width;
+    }
+    segments_min_distance(a, b, c, d) < 2.0 * width
+}
+
+// The old test: whether ab or either of its offset copies crosses cd or
+// either of its offset copies. Kept for comparison.
+pub fn approx_intersection_with_width(
     a: V2,
     b: V2,
     c: V2,
@@ -39,21 +80,52 @@ pub(crate) fn intersection_with_width(
     false
 }
 
-pub(crate) fn displace_by(a: V2, b: V2, radians: f64, offset: f64) -> (V2, V2) {
-    let ab_norm = {
-        let d = (b - a).normalise();
-        let cr = radians.cos();
-        let sr = radians.sin();
-        V2 {
-            x: d.x * cr - d.y * sr,
-            y: d.x * sr + d.y * cr,
-        } * offset
+// Exact distance between the closest points of ab and cd, from the usual
+// parametric form a + s(b - a), c + t(d - c) with s and t clamped to [0, 1].
+pub fn segments_min_distance(a: V2, b: V2, c: V2, d: V2) -> f64 {
+    let u = b - a;
+    let v = d - c;
+    let w = a - c;
+    let uu = u.length_squared();
+    let vv = v.length_squared();
+    let uv = V2::dot(u, v);
+    let uw = V2::dot(u, w);
+    let vw = V2::dot(v, w);
+    let (s, t) = if uu <= f64::EPSILON && vv <= f64::EPSILON {
+        // Both are points.
+        (0.0, 0.0)
+    } else if uu <= f64::EPSILON {
+        (0.0, (vw / vv).clamp(0.0, 1.0))
+    } else if vv <= f64::EPSILON {
+        ((-uw / uu).clamp(0.0, 1.0), 0.0)
+    } else {
+        let denom = uu * vv - uv * uv;
+        // Parallel segments have no unique closest pair, so start from a.
+        let s = if denom <= f64::EPSILON * uu * vv {
+            0.0
+        } else {
+            ((uv * vw - vv * uw) / denom).clamp(0.0, 1.0)
+        };
+        // Closest point on cd to a + su, then pull s back if t was clamped.
+        let t = (uv * s + vw) / vv;
+        if t < 0.0 {
+            ((-uw / uu).clamp(0.0, 1.0), 0.0)
+        } else if t > 1.0 {
+            (((uv - uw) / uu).clamp(0.0, 1.0), 1.0)
+        } else {
+            (s, t)
+        }
     };
+    (a + u * s - (c + v * t)).length()
+}
+
+pub fn displace_by(a: V2, b: V2, radians: f64, offset: f64) -> (V2, V2) {
+    let ab_norm = (b - a).normalise().rotate(radians) * offset;
 
     (a + ab_norm, b + ab_norm)
 }
 
-pub(crate) fn intersection(a: V2, b: V2, c: V2, d: V2) -> bool {
+pub fn intersection(a: V2, b: V2, c: V2, d: V2) -> bool {
     let oa = orient(c, d, a);
     let ob = orient(c, d, b);
     let oc = orient(a, b, c);
@@ -62,6 +134,33 @@ pub(crate) fn intersection(a: V2, b: V2, c: V2, d: V2) -> bool {
     oa * ob < 0.0 && oc * od < 0.0
 }
 
+// Tolerance for a point lying exactly on a segment, allowing for rounding in
+// the projection.
+pub(crate) const ON_SEGMENT: f64 = 1e-9;
+
+// Whether `p` lies within `tolerance` of the segment ab, ends included.
+pub(crate) fn point_on_segment(p: V2, a: V2, b: V2, tolerance: f64) -> bool {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    let t = if length_squared > 0.0 {
+        (V2::dot(p - a, ab) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let closest = a + ab * t;
+    (p - closest).length() < tolerance
+}
+
+// Like `intersection`, but segments that only touch, meet in a T, or overlap
+// along a line also count.
+pub fn intersection_proper_or_touching(a: V2, b: V2, c: V2, d: V2) -> bool {
+    intersection(a, b, c, d)
+        || point_on_segment(a, c, d, ON_SEGMENT)
+        || point_on_segment(b, c, d, ON_SEGMENT)
+        || point_on_segment(c, a, b, ON_SEGMENT)
+        || point_on_segment(d, a, b, ON_SEGMENT)
+}
+
 #[test]
 fn test_intersection() {
     let a = V2 { x: 0.0, y: 0.0 };
@@ -75,3 +174,120 @@ fn test_intersection() {
     let d = V2 { x: 0.5, y: -1.0 };
     assert!(intersection(a, b, c, d));
 }
+
+#[test]
+fn test_aabb_overlap() {
+    let p = |x, y| V2 { x, y };
+    assert!(aabb_overlap(
+        p(0.0, 0.0),
+        p(1.0, 1.0),
+        p(0.5, 0.5),
+        p(2.0, 2.0),
+        0.0
+    ));
+    assert!(!aabb_overlap(
+        p(0.0, 0.0),
+        p(1.0, 1.0),
+        p(1.5, 0.0),
+        p(2.0, 1.0),
+        0.2
+    ));
+    assert!(aabb_overlap(
+        p(0.0, 0.0),
+        p(1.0, 1.0),
+        p(1.5, 0.0),
+        p(2.0, 1.0),
+        0.25
+    ));
+}
+
+#[test]
+fn test_point_on_segment() {
+    let a = V2 { x: 0.0, y: 0.0 };
+    let b = V2 { x: 4.0, y: 2.0 };
+    assert!(point_on_segment(a, a, b, 1e-9));
+    assert!(point_on_segment(b, a, b, 1e-9));
+    assert!(point_on_segment(V2 { x: 2.0, y: 1.0 }, a, b, 1e-9));
+    // Near the middle, within the tolerance and outside it.
+    assert!(point_on_segment(V2 { x: 2.0, y: 1.4 }, a, b, 0.5));
+    assert!(!point_on_segment(V2 { x: 2.0, y: 1.4 }, a, b, 0.3));
+    // On the line through ab but past b.
+    assert!(!point_on_segment(V2 { x: 6.0, y: 3.0 }, a, b, 1e-9));
+    assert!(point_on_segment(V2 { x: 4.2, y: 2.1 }, a, b, 0.3));
+    // A zero-length segment is a point.
+    assert!(point_on_segment(V2 { x: 0.1, y: 0.0 }, a, a, 0.2));
+    assert!(!point_on_segment(V2 { x: 0.3, y: 0.0 }, a, a, 0.2));
+}
+
+#[test]
+fn test_displace_by_perpendicular() {
+    use std::f64::consts::TAU;
+    let a = V2 { x: 1.0, y: 2.0 };
+    let b = V2 { x: 4.0, y: 6.0 };
+    let unit = (b - a).normalise();
+    // 90 degrees counterclockwise from a->b.
+    let left = V2 {
+        x: -unit.y,
+        y: unit.x,
+    };
+    let mid = (a + b) * 0.5;
+    let close = |p: V2, q: V2| (p - q).length() < 1e-9;
+    for (radians, direction) in [(TAU / 4.0, left), (-TAU / 4.0, left * -1.0), (0.0, unit)] {
+        let (a1, b1) = displace_by(a, b, radians, 2.5);
+        assert!(close((a1 + b1) * 0.5, mid + direction * 2.5));
+        assert!(((b1 - a1).length() - (b - a).length()).abs() < 1e-9);
+        assert!(close(b1 - a1, b - a));
+    }
+}
+
+#[test]
+fn test_shrink() {
+    let a = V2 { x: -2.0, y: 1.0 };
+    let b = V2 { x: 6.0, y: 5.0 };
+    let mid = V2 { x: 2.0, y: 3.0 };
+    let close = |p: V2, q: V2| (p - q).length() < 1e-9;
+    let (a1, b1) = shrink((a, b), 1.0);
+    assert!(close(a1, a) && close(b1, b));
+    let (a0, b0) = shrink((a, b), 0.0);
+    assert!(close(a0, mid) && close(b0, mid));
+    let (a5, b5) = shrink((a, b), 0.5);
+    assert!(close(a5, V2::lerp(a, b, 0.25)));
+    assert!(close(b5, V2::lerp(a, b, 0.75)));
+    for scale in [0.0, 0.3, 0.9, 1.0] {
+        let (c, d) = shrink((a, b), scale);
+        assert!(((d - c).length() - scale * (b - a).length()).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_cross_and_orient() {
+    let p = |x, y| V2 { x, y };
+    assert_eq!(cross(p(1.0, 0.0), p(0.0, 1.0)), 1.0);
+    assert_eq!(cross(p(0.0, 1.0), p(1.0, 0.0)), -1.0);
+    assert_eq!(cross(p(2.0, 1.0), p(4.0, 2.0)), 0.0);
+    let (a, b) = (p(0.0, 0.0), p(2.0, 0.0));
+    assert!(orient(a, b, p(1.0, 1.0)) > 0.0);
+    assert!(orient(a, b, p(1.0, -1.0)) < 0.0);
+    assert_eq!(orient(a, b, p(5.0, 0.0)), 0.0);
+    assert_eq!(orient(p(1.0, 1.0), p(2.0, 3.0), p(3.0, 5.0)), 0.0);
+    // Twice the area of a right triangle with legs 2 and 3.
+    assert_eq!(orient(a, b, p(0.0, 3.0)), 6.0);
+}
+
+// Whole-number coordinates keep the arithmetic exact, so the identities hold
+// with ==.
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn orient_antisymmetric(
+        a in (-1000..1000, -1000..1000),
+        b in (-1000..1000, -1000..1000),
+        c in (-1000..1000, -1000..1000),
+    ) {
+        let p = |(x, y): (i32, i32)| V2 { x: x as f64, y: y as f64 };
+        let (a, b, c) = (p(a), p(b), p(c));
+        proptest::prop_assert_eq!(orient(a, b, c), -orient(b, a, c));
+        proptest::prop_assert_eq!(orient(a, b, c), orient(b, c, a));
+        proptest::prop_assert_eq!(cross(a, b), -cross(b, a));
+    }
+}
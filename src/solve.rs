@@ -0,0 +1,193 @@
+use crate::*;
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Solver {
+    // Fewest edges.
+    #[default]
+    Bfs,
+    // Shortest physical length.
+    Astar,
+}
+
+pub fn solve(
+    solver: Solver,
+    edges: &HashSet<Edge>,
+    nodes: &[Node],
+    start: Index,
+    end: Index,
+) -> Option<Vec<Index>> {
+    match solver {
+        Solver::Bfs => solve_bfs(edges, nodes, start, end),
+        Solver::Astar => solve_astar(edges, nodes, start, end),
+    }
+}
+
+// Follows `parent` links back from `end`, which must have been reached.
+fn reconstruct(parent: &[Option<Index>], start: Index, end: Index) -> Vec<Index> {
+    let mut path = vec![end];
+    let mut u = end;
+    while u != start {
+        u = parent[u].unwrap();
+        path.push(u);
+    }
+    path.reverse();
+    path
+}
+
+// The path from `start` to `end` with the fewest edges, treating each edge as
+// undirected. None if `end` can't be reached.
+pub fn solve_bfs(
+    edges: &HashSet<Edge>,
+    nodes: &[Node],
+    start: Index,
+    end: Index,
+) -> Option<Vec<Index>> {
+    let graph = MazeGraph::from_edges(edges, nodes.len());
+    let mut parent: Vec<Option<Index>> = vec![None; nodes.len()];
+    let mut queue = VecDeque::from([start]);
+    parent[start] = Some(start);
+    while let Some(u) = queue.pop_front() {
+        if u == end {
+            break;
+        }
+        for &v in graph.neighbors(u) {
+            if parent[v].is_none() {
+                parent[v] = Some(u);
+                queue.push_back(v);
+            }
+        }
+    }
+    parent[end]?;
+    Some(reconstruct(&parent, start, end))
+}
+
+// The physically shortest path from `start` to `end`, where each edge costs
+// its Euclidean length. None if `end` can't be reached.
+pub fn solve_astar(
+    edges: &HashSet<Edge>,
+    nodes: &[Node],
+    start: Index,
+    end: Index,
+) -> Option<Vec<Index>> {
+    let graph = MazeGraph::from_edges(edges, nodes.len());
+    let distance = |a: Index, b: Index| (nodes[a].point - nodes[b].point).length();
+    let mut cost = vec![f64::INFINITY; nodes.len()];
+    let mut parent: Vec<Option<Index>> = vec![None; nodes.len()];
+    let mut open = BinaryHeap::new();
+    cost[start] = 0.0;
+    parent[start] = Some(start);
+    open.push((Reverse(OrderedFloat(distance(start, end))), start));
+    while let Some((Reverse(OrderedFloat(estimate)), u)) = open.pop() {
+        if u == end {
+            return Some(reconstruct(&parent, start, end));
+        }
+        if estimate > cost[u] + distance(u, end) {
+            // A cheaper route to `u` was found after this entry was pushed.
+            continue;
+        }
+        for &v in graph.neighbors(u) {
+            let through_u = cost[u] + distance(u, v);
+            if through_u < cost[v] {
+                cost[v] = through_u;
+                parent[v] = Some(u);
+                open.push((Reverse(OrderedFloat(through_u + distance(v, end))), v));
+            }
+        }
+    }
+    None
+}
+
+// Physical length of a path of node indices.
+pub fn path_length(nodes: &[Node], path: &[Index]) -> f64 {
+    path.windows(2)
+        .map(|pair| (nodes[pair[0]].point - nodes[pair[1]].point).length())
+        .sum()
+}
+
+#[cfg(test)]
+pub(crate) fn line_nodes(count: usize) -> Vec<Node> {
+    (0..count)
+        .map(|index| Node {
+            point: V2 {
+                x: index as f64,
+                y: 0.0,
+            },
+            index,
+        })
+        .collect()
+}
+
+#[test]
+fn test_solve_bfs() {
+    let nodes = line_nodes(6);
+    // A cycle 0-1-2-3-4-0 plus a spur 2-5.
+    let edges: HashSet<Edge> = [
+        Edge(0, 1),
+        Edge(2, 1),
+        Edge(2, 3),
+        Edge(3, 4),
+        Edge(4, 0),
+        Edge(2, 5),
+    ]
+    .into();
+    assert_eq!(solve_bfs(&edges, &nodes, 0, 3), Some(vec![0, 4, 3]));
+    assert_eq!(solve_bfs(&edges, &nodes, 5, 0), Some(vec![5, 2, 1, 0]));
+    assert_eq!(solve_bfs(&edges, &nodes, 1, 1), Some(vec![1]));
+}
+
+#[test]
+fn test_solve_bfs_disconnected() {
+    let nodes = line_nodes(4);
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(2, 3)].into();
+    assert_eq!(solve_bfs(&edges, &nodes, 0, 3), None);
+}
+
+#[test]
+fn test_solve_astar_prefers_shorter_length() {
+    // Two routes from 0 to 3: a long hop through 1, or a short detour
+    // through 2 and 4.
+    let point = |x, y| V2 { x, y };
+    let nodes: Vec<Node> = [
+        point(0.0, 0.0),
+        point(5.0, 10.0),
+        point(3.0, 0.5),
+        point(10.0, 0.0),
+        point(7.0, 0.5),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, point)| Node { point, index })
+    .collect();
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(1, 3), Edge(0, 2), Edge(2, 4), Edge(4, 3)].into();
+    let bfs = solve_bfs(&edges, &nodes, 0, 3).unwrap();
+    let astar = solve_astar(&edges, &nodes, 0, 3).unwrap();
+    assert_eq!(bfs, vec![0, 1, 3]);
+    assert_eq!(astar, vec![0, 2, 4, 3]);
+    assert!(bfs.len() < astar.len());
+    assert!(path_length(&nodes, &astar) < path_length(&nodes, &bfs));
+}
+
+#[test]
+fn test_solve_astar_matches_bfs_on_tree() {
+    let params = Params {
+        radius: 60.0,
+        ..Default::default()
+    };
+    let builder = MazeBuilder::new().params(params).seed(3);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    // A spanning tree has exactly one path between any two nodes.
+    let bfs = solve_bfs(&maze.edges, &maze.nodes, maze.start, maze.end);
+    let astar = solve_astar(&maze.edges, &maze.nodes, maze.start, maze.end);
+    assert!(bfs.is_some());
+    assert_eq!(bfs, astar);
+}
+
+#[test]
+fn test_solve_astar_disconnected() {
+    let nodes = line_nodes(4);
+    let edges: HashSet<Edge> = [Edge(0, 1), Edge(2, 3)].into();
+    assert_eq!(solve_astar(&edges, &nodes, 0, 3), None);
+}
@@ -0,0 +1,68 @@
+use crate::V2;
+use std::collections::HashMap;
+
+// Buckets points into square cells so that proximity queries only need to
+// look at the handful of cells around the query point.
+#[derive(Debug, Clone)]
+pub struct SpatialHash {
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<V2>>,
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f64) -> Self {
+        assert!(cell_size > 0.0, "cell_size must be positive");
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, point: V2) -> (i64, i64) {
+        (
+            (point.x / self.cell_size).floor() as i64,
+            (point.y / self.cell_size).floor() as i64,
+        )
+    }
+
+    pub fn insert(&mut self, point: V2) {
+        let cell = self.cell(point);
+        self.cells.entry(cell).or_default().push(point);
+    }
+
+    // Whether any inserted point lies within `distance` (inclusive) of `point`.
+    pub fn any_within(&self, point: V2, distance: f64) -> bool {
+        let reach = (distance / self.cell_size).ceil() as i64;
+        let (cx, cy) = self.cell(point);
+        for y in cy - reach..=cy + reach {
+            for x in cx - reach..=cx + reach {
+                if let Some(points) = self.cells.get(&(x, y)) {
+                    if points.iter().any(|&p| (p - point).length() <= distance) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+#[test]
+fn test_any_within_matches_linear_scan() {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(7);
+    let mut hash = SpatialHash::new(3.0);
+    let mut points: Vec<V2> = Vec::new();
+    for _ in 0..2000 {
+        let point = V2 {
+            x: rng.gen_range(-50.0..50.0),
+            y: rng.gen_range(-50.0..50.0),
+        };
+        let expected = points.iter().any(|&p| (p - point).length() <= 3.0);
+        assert_eq!(hash.any_within(point, 3.0), expected);
+        if !expected {
+            hash.insert(point);
+            points.push(point);
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use crate::seg::*;
+use crate::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::TAU;
+use std::str::FromStr;
+
+pub fn get_nearest_k(nodes: &[Node], cur: Node, k: usize) -> Vec<Node> {
+    let mut nodes: Vec<Node> = nodes.to_vec();
+    nodes.sort_by(|a, b| {
+        let a_dist: f64 = (a.point - cur.point).length_squared();
+        let b_dist: f64 = (b.point - cur.point).length_squared();
+        a_dist.partial_cmp(&b_dist).unwrap()
+    });
+    nodes.truncate(k);
+    nodes
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AngleBias {
+    // Any turn is allowed, and every neighbor is equally likely.
+    None,
+    // Turns sharper than `cutoff` radians are rejected.
+    Hard { cutoff: f64 },
+    // Any turn is allowed, but dfs tries a neighbor a turn of `diff` radians
+    // away with weight exp(-strength * diff).
+    Soft { strength: f64 },
+}
+
+impl FromStr for AngleBias {
+    type Err = String;
+
+    // Parses `none`, `hard:<radians>` or `soft:<strength>`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let number = |prefix: &str| {
+            s.strip_prefix(prefix)
+                .and_then(|n| n.parse::<f64>().ok())
+                .filter(|n| n.is_finite() && *n >= 0.0)
+        };
+        if s == "none" {
+            Ok(AngleBias::None)
+        } else if let Some(cutoff) = number("hard:") {
+            Ok(AngleBias::Hard { cutoff })
+        } else if let Some(strength) = number("soft:") {
+            Ok(AngleBias::Soft { strength })
+        } else {
+            Err(format!(
+                "expected none, hard:<radians> or soft:<strength>, got {s:?}"
+            ))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DfsFrame {
+    pub(crate) prior: V2,
+    pub(crate) current: Node,
+    shuffled_neighbors: Vec<Node>,
+    neighbor_cursor: usize,
+    pub(crate) depth: usize,
+}
+
+impl DfsFrame {
+    pub(crate) fn new(
+        rng: &mut impl Rng,
+        params: &Params,
+        cache: &NeighborCache,
+        prior: V2,
+        current: Node,
+        depth: usize,
+    ) -> Self {
+        let mut shuffled_neighbors = cache.get(current.index).to_vec();
+        match params.angle_bias {
+            Some(AngleBias::Soft { strength }) => {
+                // Draw the order one neighbor at a time, favoring gentle turns.
+                let heading = current.point - prior;
+                let mut remaining = shuffled_neighbors;
+                shuffled_neighbors = Vec::with_capacity(remaining.len());
+                while !remaining.is_empty() {
+                    let weights = remaining.iter().map(|n| {
+                        let turn = heading.angle_to(n.point - current.point);
+                        (-strength * turn).exp()
+                    });
+                    let i = match WeightedIndex::new(weights) {
+                        Ok(dist) => dist.sample(rng),
+                        // Every weight underflowed to zero.
+                        Err(_) => rng.gen_range(0..remaining.len()),
+                    };
+                    shuffled_neighbors.push(remaining.swap_remove(i));
+                }
+            }
+            _ => shuffled_neighbors.shuffle(rng),
+        }
+        Self {
+            prior,
+            current,
+            shuffled_neighbors,
+            neighbor_cursor: 0,
+            depth,
+        }
+    }
+
+    // The next neighbor to try, or None once all of them have been tried.
+    pub(crate) fn next_neighbor(&mut self) -> Option<Node> {
+        let node = self.shuffled_neighbors.get(self.neighbor_cursor).cloned();
+        self.neighbor_cursor += 1;
+        node
+    }
+}
+
+// How many of `edges` meet at each of `node_count` nodes.
+pub(crate) fn edge_degrees(edges: &HashSet<Edge>, node_count: usize) -> Vec<usize> {
+    let mut degrees = vec![0; node_count];
+    for &Edge(a, b) in edges {
+        degrees[a] += 1;
+        degrees[b] += 1;
+    }
+    degrees
+}
+
+// Whether one more passage at each of `a` and `b` stays within
+// `params.max_degree`.
+pub(crate) fn degree_allows(params: &Params, degrees: &[usize], a: Index, b: Index) -> bool {
+    params
+        .max_degree
+        .is_none_or(|max| degrees[a] < max && degrees[b] < max)
+}
+
+// Whether dfs may extend the maze along `current` -> `node`, having arrived at
+// `current` from `prior`.
+pub(crate) fn dfs_accepts(
+    params: &Params,
+    prior: V2,
+    current: Node,
+    node: Node,
+    edges: &HashSet<Edge>,
+    nodes: &[Node],
+    midpoints: &[V2],
+) -> bool {
+    let edge = Edge(current.index, node.index);
+    let diff = (current.point - prior).angle_to(node.point - current.point);
+    if diff > params.turn_limit() {
+        // println!("bailing AAAAA");
+        return false;
+    }
+    if edge_intersects(params, edge, edges, nodes) {
+        return false;
+    }
+    let midpoint = (node.point + current.point) * 0.5;
+    midpoints
+        .iter()
+        .all(|&m| (m - midpoint).length() > params.min_spacing * 0.8)
+        && nodes.iter().all(|n| {
+            n.index == node.index
+                || n.index == current.index
+                || (n.point - midpoint).length() > params.tube_radius * 2.0
+        })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn dfs(
+    rng: &mut impl Rng,
+    params: &Params,
+    prior: V2,
+    current: Node,
+    edges: &mut HashSet<Edge>,
+    visited: &mut HashSet<Index>,
+    nodes: &[Node],
+    cache: &NeighborCache,
+    midpoints: &mut Vec<V2>,
+    max_depth_index: &mut (usize, usize),
+    depths: &mut HashMap<Edge, usize>,
+    observer: &mut dyn MazeObserver,
+) {
+    if params.loop_erase {
+        return loop_erased_dfs(
+            rng,
+            params,
+            prior,
+            current,
+            edges,
+            visited,
+            nodes,
+            cache,
+            midpoints,
+            max_depth_index,
+            depths,
+            observer,
+        );
+    }
+    let mut stack: Vec<DfsFrame> = vec![DfsFrame::new(rng, params, cache, prior, current, 0)];
+    let mut degrees = edge_degrees(edges, nodes.len());
+    while let Some(frame) = stack.last_mut() {
+        let Some(node) = frame.next_neighbor() else {
+            // Every neighbor of this node has been tried, so backtrack.
+            stack.pop();
+            continue;
+        };
+        let DfsFrame {
+            prior,
+            current,
+            depth,
+            ..
+        } = *frame;
+        if visited.contains(&node.index)
+            || !degree_allows(params, &degrees, current.index, node.index)
+            || !dfs_accepts(params, prior, current, node, edges, nodes, midpoints)
+        {
+            continue;
+        }
+        degrees[current.index] += 1;
+        degrees[node.index] += 1;
+        if depth > max_depth_index.0 {
+            *max_depth_index = (depth, node.index);
+        }
+        midpoints.push((node.point + current.point) * 0.5);
+        visited.insert(node.index);
+        edges.insert(Edge(current.index, node.index));
+        depths.insert(Edge(current.index, node.index), depth);
+        observer.on_edge_added(Edge(current.index, node.index), nodes);
+        observer.on_node_visited(&node, depth + 1);
+        stack.push(DfsFrame::new(
+            rng,
+            params,
+            cache,
+            current.point,
+            node,
+            depth + 1,
+        ));
+    }
+}
+
+// Steps, per node, a loop-erased walk may take before giving up on reaching
+// the maze, as it never will from a part of the neighbor graph cut off from
+// it.
+const WALK_STEPS_PER_NODE: usize = 20;
+
+// dfs by Wilson's algorithm: from each node not yet in the maze, in random
+// order, walk at random through `cache` until reaching the maze, erasing any
+// loop the walk makes, then carve the walk from the maze end back. The walk
+// only keeps to the turn limit; the rest of `dfs_accepts` is checked as it is
+// carved, and carving stops at the first passage that fails, leaving the rest
+// for later walks. This avoids the long corridors of plain dfs.
+#[allow(clippy::too_many_arguments)]
+fn loop_erased_dfs(
+    rng: &mut impl Rng,
+    params: &Params,
+    prior: V2,
+    current: Node,
+    edges: &mut HashSet<Edge>,
+    visited: &mut HashSet<Index>,
+    nodes: &[Node],
+    cache: &NeighborCache,
+    midpoints: &mut Vec<V2>,
+    max_depth_index: &mut (usize, usize),
+    depths: &mut HashMap<Edge, usize>,
+    observer: &mut dyn MazeObserver,
+) {
+    let mut degrees = edge_degrees(edges, nodes.len());
+    // Where the passage into each node in the maze comes from, and how deep
+    // the node is.
+    let mut priors: HashMap<Index, V2> = [(current.index, prior)].into();
+    let mut node_depths: HashMap<Index, usize> = [(current.index, 0)].into();
+    let mut order: Vec<Index> = nodes.iter().map(|n| n.index).collect();
+    let mut walk_path: Vec<Index> = Vec::new();
+    let mut on_walk: HashMap<Index, usize> = HashMap::new();
+    // Walks cut short by a failed passage are tried again on the next pass,
+    // for as long as passes keep growing the maze.
+    let mut last_count = 0;
+    while visited.len() > last_count {
+        last_count = visited.len();
+        order.retain(|i| !visited.contains(i));
+        order.shuffle(rng);
+        for &root in &order {
+            if visited.contains(&root) {
+                continue;
+            }
+            walk_path.clear();
+            on_walk.clear();
+            walk_path.push(root);
+            on_walk.insert(root, 0);
+            let mut reached = None;
+            for _ in 0..nodes.len() * WALK_STEPS_PER_NODE {
+                let u = *walk_path.last().unwrap();
+                let Some(&w) = cache.get(u).choose(rng) else {
+                    break;
+                };
+                if w.index == u {
+                    continue;
+                }
+                if let [.., before, _] = walk_path[..] {
+                    let heading = nodes[u].point - nodes[before].point;
+                    if heading.angle_to(w.point - nodes[u].point) > params.turn_limit() {
+                        continue;
+                    }
+                }
+                if visited.contains(&w.index) {
+                    reached = Some(w.index);
+                    break;
+                }
+                if let Some(&i) = on_walk.get(&w.index) {
+                    for erased in walk_path.drain(i + 1..) {
+                        on_walk.remove(&erased);
+                    }
+                } else {
+                    on_walk.insert(w.index, walk_path.len());
+                    walk_path.push(w.index);
+                }
+            }
+            let Some(mut at) = reached else {
+                continue;
+            };
+            for &next in walk_path.iter().rev() {
+                let (current, node) = (nodes[at], nodes[next]);
+                let prior = priors[&at];
+                if !degree_allows(params, &degrees, at, next)
+                    || !dfs_accepts(params, prior, current, node, edges, nodes, midpoints)
+                {
+                    break;
+                }
+                let depth = node_depths[&at];
+                if depth > max_depth_index.0 {
+                    *max_depth_index = (depth, next);
+                }
+                let edge = Edge(at, next);
+                midpoints.push((node.point + current.point) * 0.5);
+                visited.insert(next);
+                edges.insert(edge);
+                depths.insert(edge, depth);
+                degrees[at] += 1;
+                degrees[next] += 1;
+                priors.insert(next, current.point);
+                node_depths.insert(next, depth + 1);
+                observer.on_edge_added(edge, nodes);
+                observer.on_node_visited(&node, depth + 1);
+                at = next;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct QueueItem {
+    prior: V2,
+    current: Node,
+    next: Node,
+    depth: usize,
+}
+
+fn enqueue_nearest(
+    rng: &mut impl Rng,
+    prior: V2,
+    cache: &NeighborCache,
+    current: Node,
+    depth: usize,
+    queue: &mut VecDeque<QueueItem>,
+) {
+    // if depth > 15 { return; }
+    let mut nearest_nodes = cache.get(current.index).to_vec();
+    nearest_nodes.shuffle(rng);
+    for node in nearest_nodes {
+        queue.push_back(QueueItem {
+            prior,
+            current,
+            next: node,
+            depth,
+        });
+    }
+    // queue.shuffle(rng);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn bfs(
+    rng: &mut impl Rng,
+    params: &Params,
+    prior: V2,
+    current: Node,
+    edges: &mut HashSet<Edge>,
+    visited: &mut HashSet<Index>,
+    nodes: &[Node],
+    cache: &NeighborCache,
+    midpoints: &mut Vec<V2>,
+    max_depth_index: &mut (usize, usize),
+    depths: &mut HashMap<Edge, usize>,
+    observer: &mut dyn MazeObserver,
+) {
+    let mut queue: VecDeque<QueueItem> = Default::default();
+    let mut degrees = edge_degrees(edges, nodes.len());
+    enqueue_nearest(rng, prior, cache, current, 1, &mut queue);
+    while let Some(QueueItem {
+        prior,
+        current,
+        next: node,
+        depth,
+    }) = queue.pop_front()
+    {
+        if !visited.contains(&node.index)
+            && degree_allows(params, &degrees, current.index, node.index)
+        {
+            let edge = Edge(current.index, node.index);
+            let diff = (current.point - prior).angle_to(node.point - current.point);
+            if diff > params.turn_limit() {
+                continue;
+            }
+            if edge_intersects(params, edge, edges, nodes) {
+                continue;
+            }
+            let midpoint = (node.point + current.point) * 0.5;
+            if midpoints
+                .iter()
+                .all(|&m| (m - midpoint).length() > params.min_spacing * 0.8)
+                && nodes
+                    .iter()
+                    .all(|n| (n.point - midpoint).length() > params.tube_radius * 2.1)
+            {
+                if depth > max_depth_index.0 {
+                    *max_depth_index = (depth, node.index);
+                }
+                midpoints.push(midpoint);
+                visited.insert(node.index);
+                edges.insert(edge);
+                degrees[current.index] += 1;
+                degrees[node.index] += 1;
+                depths.insert(edge, depth);
+                observer.on_edge_added(edge, nodes);
+                observer.on_node_visited(&node, depth);
+                enqueue_nearest(rng, current.point, cache, node, depth + 1, &mut queue);
+            }
+        }
+    }
+}
+
+// The smaller angle between two headings, in [0, PI].
+pub fn radian_diff(a: f64, b: f64) -> f64 {
+    // Taking abs first keeps this exactly symmetric.
+    let d = (a - b).abs() % TAU;
+    d.min(TAU - d)
+}
+
+pub fn edge_intersects(params: &Params, edge: Edge, edges: &HashSet<Edge>, nodes: &[Node]) -> bool {
+    let Edge(a, b) = edge;
+    for &Edge(c, d) in edges {
+        if intersection_with_width(
+            nodes[a].point,
+            nodes[b].point,
+            nodes[c].point,
+            nodes[d].point,
+            params.tube_radius,
+        ) {
+            return true;
+        }
+    }
+    false
+}
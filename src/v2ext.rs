@@ -0,0 +1,106 @@
+use crate::*;
+use vector2d::Vector2D;
+
+// Geometry on vectors that `vector2d` doesn't provide. Angles turn from the
+// positive x axis toward positive y, which is clockwise on screen.
+pub trait V2Ext {
+    // The smaller angle between the two directions, in [0, PI].
+    fn angle_to(&self, other: V2) -> f64;
+    fn rotate(&self, radians: f64) -> V2;
+    // The zero vector when `other` is zero.
+    fn project_onto(&self, other: V2) -> V2;
+    // The mirror image across the line through the origin along `axis`.
+    fn reflect_over(&self, axis: V2) -> V2;
+    // Shortened to `max` if longer, with the same direction.
+    fn clamp_length(&self, max: f64) -> V2;
+}
+
+impl V2Ext for Vector2D<f64> {
+    fn angle_to(&self, other: V2) -> f64 {
+        radian_diff(other.angle(), self.angle())
+    }
+
+    fn rotate(&self, radians: f64) -> V2 {
+        let (sin, cos) = radians.sin_cos();
+        V2 {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+
+    fn project_onto(&self, other: V2) -> V2 {
+        let length_squared = other.length_squared();
+        if length_squared == 0.0 {
+            return V2 { x: 0.0, y: 0.0 };
+        }
+        other * (V2::dot(*self, other) / length_squared)
+    }
+
+    fn reflect_over(&self, axis: V2) -> V2 {
+        self.project_onto(axis) * 2.0 - *self
+    }
+
+    fn clamp_length(&self, max: f64) -> V2 {
+        let length = self.length();
+        if length > max {
+            *self * (max / length)
+        } else {
+            *self
+        }
+    }
+}
+
+#[cfg(test)]
+fn assert_near(a: V2, b: V2) {
+    assert!((a - b).length() < 1e-12, "{a:?} != {b:?}");
+}
+
+#[test]
+fn test_angle_to() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let right = V2 { x: 1.0, y: 0.0 };
+    assert_eq!(right.angle_to(V2 { x: 0.0, y: 3.0 }), FRAC_PI_2);
+    assert_eq!(right.angle_to(V2 { x: 0.0, y: -3.0 }), FRAC_PI_2);
+    assert_eq!(right.angle_to(V2 { x: -2.0, y: 0.0 }), PI);
+    assert_eq!(right.angle_to(right * 5.0), 0.0);
+}
+
+#[test]
+fn test_rotate() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let v = V2 { x: 2.0, y: 1.0 };
+    assert_near(v.rotate(FRAC_PI_2), V2 { x: -1.0, y: 2.0 });
+    assert_near(v.rotate(PI), V2 { x: -2.0, y: -1.0 });
+    assert_near(v.rotate(-FRAC_PI_2), V2 { x: 1.0, y: -2.0 });
+    assert_eq!(v.rotate(0.0), v);
+}
+
+#[test]
+fn test_project_onto() {
+    let v = V2 { x: 3.0, y: 4.0 };
+    assert_eq!(v.project_onto(V2 { x: 2.0, y: 0.0 }), V2 { x: 3.0, y: 0.0 });
+    assert_eq!(v.project_onto(V2 { x: 1.0, y: 1.0 }), V2 { x: 3.5, y: 3.5 });
+    assert_eq!(v.project_onto(V2 { x: 0.0, y: 0.0 }), V2 { x: 0.0, y: 0.0 });
+}
+
+#[test]
+fn test_reflect_over() {
+    let v = V2 { x: 3.0, y: 4.0 };
+    assert_eq!(
+        v.reflect_over(V2 { x: 1.0, y: 0.0 }),
+        V2 { x: 3.0, y: -4.0 }
+    );
+    assert_eq!(
+        v.reflect_over(V2 { x: 0.0, y: 2.0 }),
+        V2 { x: -3.0, y: 4.0 }
+    );
+    assert_eq!(v.reflect_over(V2 { x: 1.0, y: 1.0 }), V2 { x: 4.0, y: 3.0 });
+}
+
+#[test]
+fn test_clamp_length() {
+    let v = V2 { x: 3.0, y: 4.0 };
+    assert_eq!(v.clamp_length(10.0), v);
+    assert_eq!(v.clamp_length(5.0), v);
+    assert_eq!(v.clamp_length(2.5), V2 { x: 1.5, y: 2.0 });
+}
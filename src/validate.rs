@@ -0,0 +1,171 @@
+use crate::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    OutOfRange(Edge),
+    SelfLoop(Index),
+    // The same passage in both directions.
+    DuplicateEdge(Edge),
+    EdgeCount { expected: usize, found: usize },
+    // The first node a search from node 0 doesn't reach.
+    Disconnected(Index),
+    // A passage runs straight through a node it doesn't end at.
+    NodeOnEdge { node: Index, edge: Edge },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::OutOfRange(Edge(a, b)) => write!(f, "edge {a}-{b} is out of range"),
+            Self::SelfLoop(i) => write!(f, "node {i} has a passage to itself"),
+            Self::DuplicateEdge(Edge(a, b)) => write!(f, "edge {a}-{b} appears twice"),
+            Self::EdgeCount { expected, found } => {
+                write!(f, "expected {expected} edges, found {found}")
+            }
+            Self::Disconnected(i) => write!(f, "node {i} can't be reached"),
+            Self::NodeOnEdge {
+                node,
+                edge: Edge(a, b),
+            } => write!(f, "node {node} lies on edge {a}-{b}"),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+pub fn verify_spanning_tree(
+    edges: &HashSet<Edge>,
+    node_count: usize,
+) -> std::result::Result<(), ValidationError> {
+    let mut seen: HashSet<(Index, Index)> = HashSet::new();
+    for &Edge(a, b) in edges {
+        if a >= node_count || b >= node_count {
+            return Err(ValidationError::OutOfRange(Edge(a, b)));
+        }
+        if a == b {
+            return Err(ValidationError::SelfLoop(a));
+        }
+        if !seen.insert((a.min(b), a.max(b))) {
+            return Err(ValidationError::DuplicateEdge(Edge(a, b)));
+        }
+    }
+    let expected = node_count.saturating_sub(1);
+    if edges.len() != expected {
+        return Err(ValidationError::EdgeCount {
+            expected,
+            found: edges.len(),
+        });
+    }
+    if node_count == 0 {
+        return Ok(());
+    }
+    let hops = MazeGraph::from_edges(edges, node_count).hops_from(0);
+    match hops.iter().position(Option::is_none) {
+        Some(i) => Err(ValidationError::Disconnected(i)),
+        None => Ok(()),
+    }
+}
+
+// Checks the carved part of a maze: the start and every node with a passage.
+// Nodes the generator never reached are left out, as they aren't drawn.
+pub fn verify_maze(maze: &Maze) -> std::result::Result<(), ValidationError> {
+    // `carved[i]` is the maze index of compact index `i`.
+    let mut carved: Vec<Index> = vec![maze.start];
+    let mut compact: HashMap<Index, Index> = HashMap::from([(maze.start, 0)]);
+    let mut edges: HashSet<Edge> = HashSet::new();
+    for Edge(a, b) in maze.sorted_edges() {
+        if a >= maze.nodes.len() || b >= maze.nodes.len() {
+            return Err(ValidationError::OutOfRange(Edge(a, b)));
+        }
+        let [a, b] = [a, b].map(|i| {
+            *compact.entry(i).or_insert_with(|| {
+                carved.push(i);
+                carved.len() - 1
+            })
+        });
+        edges.insert(Edge(a, b));
+    }
+    verify_spanning_tree(&edges, carved.len()).map_err(|e| match e {
+        ValidationError::SelfLoop(i) => ValidationError::SelfLoop(carved[i]),
+        ValidationError::DuplicateEdge(Edge(a, b)) => {
+            ValidationError::DuplicateEdge(Edge(carved[a], carved[b]))
+        }
+        ValidationError::Disconnected(i) => ValidationError::Disconnected(carved[i]),
+        e => e,
+    })?;
+    for edge @ Edge(a, b) in maze.sorted_edges() {
+        let (pa, pb) = (maze.nodes[a].point, maze.nodes[b].point);
+        for &node in &carved {
+            if node != a
+                && node != b
+                && seg::point_on_segment(maze.nodes[node].point, pa, pb, seg::ON_SEGMENT)
+            {
+                return Err(ValidationError::NodeOnEdge { node, edge });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_verify_spanning_tree() {
+    let tree: HashSet<Edge> = [Edge(0, 1), Edge(1, 2), Edge(1, 3)].into();
+    assert_eq!(verify_spanning_tree(&tree, 4), Ok(()));
+    assert_eq!(verify_spanning_tree(&HashSet::new(), 1), Ok(()));
+    assert_eq!(
+        verify_spanning_tree(&tree, 5),
+        Err(ValidationError::EdgeCount {
+            expected: 4,
+            found: 3
+        })
+    );
+
+    let self_loop: HashSet<Edge> = [Edge(0, 1), Edge(2, 2)].into();
+    assert_eq!(
+        verify_spanning_tree(&self_loop, 3),
+        Err(ValidationError::SelfLoop(2))
+    );
+
+    let both_ways: HashSet<Edge> = [Edge(0, 1), Edge(1, 0)].into();
+    assert!(matches!(
+        verify_spanning_tree(&both_ways, 3),
+        Err(ValidationError::DuplicateEdge(_))
+    ));
+
+    // Right number of edges, but 0-1-2 is a loop and 3 is cut off.
+    let cycle: HashSet<Edge> = [Edge(0, 1), Edge(1, 2), Edge(2, 0)].into();
+    assert_eq!(
+        verify_spanning_tree(&cycle, 4),
+        Err(ValidationError::Disconnected(3))
+    );
+
+    let out_of_range: HashSet<Edge> = [Edge(0, 7)].into();
+    assert_eq!(
+        verify_spanning_tree(&out_of_range, 2),
+        Err(ValidationError::OutOfRange(Edge(0, 7)))
+    );
+}
+
+#[test]
+fn test_verify_maze_node_on_edge() {
+    let builder = MazeBuilder::new().radius(30.0).seed(1);
+    let mut maze = builder.build(&mut builder.rng()).unwrap();
+    assert_eq!(verify_maze(&maze), Ok(()));
+    // Move a node onto the middle of a passage it isn't part of.
+    let Edge(a, b) = maze.sorted_edges()[0];
+    let node = maze.sorted_edges()[1..]
+        .iter()
+        .flat_map(|e| [e.0, e.1])
+        .find(|&i| i != a && i != b)
+        .unwrap();
+    maze.nodes[node].point = (maze.nodes[a].point + maze.nodes[b].point) * 0.5;
+    assert_eq!(
+        verify_maze(&maze),
+        Err(ValidationError::NodeOnEdge {
+            node,
+            edge: Edge(a, b)
+        })
+    );
+}
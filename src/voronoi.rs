@@ -0,0 +1,186 @@
+use crate::*;
+use svg::node::element::path::Data;
+
+// Sides of the polygon standing in for a circular boundary.
+pub const VORONOI_CIRCLE_SIDES: usize = 96;
+
+// Neighbors clipped against first; doubled until the cell is settled.
+const VORONOI_START_K: usize = 16;
+
+// The boundary as a polygon that cells are cut out of.
+fn boundary_polygon(boundary: &BoundaryShape) -> Vec<V2> {
+    match boundary {
+        BoundaryShape::Circle { radius } => (0..VORONOI_CIRCLE_SIDES)
+            .map(|i| {
+                let angle = i as f64 / VORONOI_CIRCLE_SIDES as f64 * std::f64::consts::TAU;
+                V2 {
+                    x: radius * angle.cos(),
+                    y: radius * angle.sin(),
+                }
+            })
+            .collect(),
+        BoundaryShape::Rectangle { width, height } => {
+            let (w, h) = (width / 2.0, height / 2.0);
+            vec![
+                V2 { x: -w, y: -h },
+                V2 { x: w, y: -h },
+                V2 { x: w, y: h },
+                V2 { x: -w, y: h },
+            ]
+        }
+        BoundaryShape::Polygon { vertices } => vertices.clone(),
+    }
+}
+
+// The part of `polygon` closer to `site` than to `other` (Sutherland-Hodgman
+// against their bisector).
+fn clip_to_bisector(polygon: &[V2], site: V2, other: V2) -> Vec<V2> {
+    let mid = (site + other) * 0.5;
+    let normal = other - site;
+    let side = |p: V2| V2::dot(p - mid, normal);
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let (sa, sb) = (side(a), side(b));
+        if sa <= 0.0 {
+            clipped.push(a);
+        }
+        if (sa <= 0.0) != (sb <= 0.0) {
+            clipped.push(a + (b - a) * (sa / (sa - sb)));
+        }
+    }
+    clipped
+}
+
+// Clips `cell` against `others` in order of distance from `site`, stopping
+// once the rest are too far away to reach it. Returns the cell and whether
+// the stop was reached before running out of `others`.
+fn clip_cell(mut cell: Vec<V2>, site: V2, others: &[V2]) -> (Vec<V2>, bool) {
+    for &other in others {
+        let reach = cell
+            .iter()
+            .map(|&p| (p - site).length())
+            .fold(0.0, f64::max);
+        if (other - site).length() > 2.0 * reach {
+            return (cell, true);
+        }
+        // Nodes on top of each other have no bisector.
+        if (other - site).length() > 1e-12 {
+            cell = clip_to_bisector(&cell, site, other);
+        }
+    }
+    (cell, false)
+}
+
+// The region of `boundary` closer to `node` than to any other of `nodes`.
+pub fn voronoi_cell(node: &Node, nodes: &[Node], boundary: &BoundaryShape) -> Vec<V2> {
+    let mut others: Vec<V2> = nodes
+        .iter()
+        .filter(|n| n.index != node.index)
+        .map(|n| n.point)
+        .collect();
+    others.sort_by(|a, b| {
+        (*a - node.point)
+            .length_squared()
+            .total_cmp(&(*b - node.point).length_squared())
+    });
+    clip_cell(boundary_polygon(boundary), node.point, &others).0
+}
+
+pub fn voronoi_path(node: &Node, nodes: &[Node], boundary: &BoundaryShape) -> Data {
+    polygon_data(&voronoi_cell(node, nodes, boundary))
+}
+
+// Every node's cell, using the k-d tree instead of sorting all the nodes
+// for each one.
+pub fn voronoi_cells(nodes: &[Node], boundary: &BoundaryShape) -> Vec<Vec<V2>> {
+    let tree = NodeTree::new(nodes);
+    let outline = boundary_polygon(boundary);
+    nodes
+        .iter()
+        .map(|node| {
+            let mut k = VORONOI_START_K;
+            loop {
+                let others: Vec<V2> = tree
+                    .nearest_k(*node, k + 1)
+                    .into_iter()
+                    .filter(|n| n.index != node.index)
+                    .map(|n| n.point)
+                    .collect();
+                let (cell, settled) = clip_cell(outline.clone(), node.point, &others);
+                if settled || k + 1 >= nodes.len() {
+                    break cell;
+                }
+                k *= 2;
+            }
+        })
+        .collect()
+}
+
+pub fn polygon_data(polygon: &[V2]) -> Data {
+    let mut data = Data::new();
+    for (i, p) in polygon.iter().enumerate() {
+        data = if i == 0 {
+            data.move_to((p.x, p.y))
+        } else {
+            data.line_to((p.x, p.y))
+        };
+    }
+    data.close()
+}
+
+// Cells are convex, so the polygon area works for them too.
+#[cfg(test)]
+fn polygon_area(polygon: &[V2]) -> f64 {
+    BoundaryShape::Polygon {
+        vertices: polygon.to_vec(),
+    }
+    .area()
+}
+
+#[test]
+fn test_voronoi_cell_square() {
+    let boundary = BoundaryShape::Rectangle {
+        width: 4.0,
+        height: 4.0,
+    };
+    let nodes: Vec<Node> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .iter()
+        .enumerate()
+        .map(|(index, &(x, y))| Node {
+            point: V2 { x, y },
+            index,
+        })
+        .collect();
+    for node in &nodes {
+        let cell = voronoi_cell(node, &nodes, &boundary);
+        assert!((polygon_area(&cell) - 4.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_voronoi_cells_cover_boundary() {
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(5);
+    let boundary = BoundaryShape::Circle { radius: 10.0 };
+    let nodes: Vec<Node> = (0..200)
+        .map(|index| {
+            let (r, angle) = (rng.gen_range(0.0..9.0f64), rng.gen_range(0.0..6.3f64));
+            Node {
+                point: V2 {
+                    x: r * angle.cos(),
+                    y: r * angle.sin(),
+                },
+                index,
+            }
+        })
+        .collect();
+    let cells = voronoi_cells(&nodes, &boundary);
+    let total: f64 = cells.iter().map(|cell| polygon_area(cell)).sum();
+    assert!((total - polygon_area(&boundary_polygon(&boundary))).abs() < 1e-6);
+    // The fast path finds the same cells as clipping against every node.
+    for node in nodes.iter().step_by(20) {
+        let cell = voronoi_cell(node, &nodes, &boundary);
+        assert!((polygon_area(&cells[node.index]) - polygon_area(&cell)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,78 @@
+use maze::*;
+
+// Rounds every decimal number to two places, so the fixtures only change
+// when the drawing does and not with floating point noise.
+fn normalize(svg: &str) -> String {
+    let mut out = String::with_capacity(svg.len());
+    let mut chars = svg.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if !(c.is_ascii_digit() || c == '-') {
+            out.push(c);
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some(&(i, c)) = chars.peek() {
+            if !(c.is_ascii_digit() || c == '.' || c == 'e') {
+                break;
+            }
+            end = i + 1;
+            chars.next();
+        }
+        let token = &svg[start..end];
+        match token.parse::<f64>() {
+            Ok(value) if token.contains('.') && !token.ends_with('.') => {
+                let rounded = format!("{value:.2}");
+                out.push_str(if rounded == "-0.00" { "0.00" } else { &rounded });
+            }
+            _ => out.push_str(token),
+        }
+    }
+    out
+}
+
+// Regenerate with UPDATE_GOLDEN=1 cargo test.
+fn check_fixture(name: &str, layout: NodeLayout, algorithm: Algorithm) {
+    let builder = MazeBuilder::new()
+        .radius(60.0)
+        .layout(layout)
+        .algorithm(algorithm)
+        .seed(42);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let actual = normalize(&render_svg(&maze, &RenderOptions::default()).to_string()) + "\n";
+    let path = format!("{}/tests/golden/{name}.svg", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        actual == expected,
+        "{name}.svg differs; rerun with UPDATE_GOLDEN=1 if the change is intended"
+    );
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(
+        normalize("<circle cx=\"-0.0001\" cy=\"12.345678\" r=\"2\"/> #1E90FF"),
+        "<circle cx=\"0.00\" cy=\"12.35\" r=\"2\"/> #1E90FF"
+    );
+    assert_eq!(
+        normalize("xmlns=\"http://www.w3.org/2000/svg\""),
+        "xmlns=\"http://www.w3.org/2000/svg\""
+    );
+}
+
+#[test]
+fn test_golden_grid_dfs() {
+    check_fixture("grid_dfs", NodeLayout::Grid, Algorithm::Dfs);
+}
+
+#[test]
+fn test_golden_spiral_dfs() {
+    check_fixture("spiral_dfs", NodeLayout::Spiral, Algorithm::Dfs);
+}
+
+#[test]
+fn test_golden_random_bfs() {
+    check_fixture("random_bfs", NodeLayout::Random, Algorithm::Bfs);
+}
@@ -0,0 +1,1198 @@
+use maze::*;
+use std::collections::HashSet;
+use std::time::Duration;
+
+fn small_builder() -> MazeBuilder {
+    MazeBuilder::new()
+        .params(Params {
+            radius: 60.0,
+            ..Default::default()
+        })
+        .seed(42)
+}
+
+#[test]
+fn test_build_grid_dfs() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert!(!maze.nodes.is_empty());
+    assert!(!maze.edges.is_empty());
+    for &Edge(a, b) in &maze.edges {
+        assert_ne!(a, b);
+        assert!(a < maze.nodes.len() && b < maze.nodes.len());
+    }
+}
+
+#[test]
+fn test_build_spiral_bfs() {
+    let builder = small_builder()
+        .layout(NodeLayout::Spiral)
+        .algorithm(Algorithm::Bfs);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let visited: HashSet<Index> = maze.edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    // Every edge adds exactly one new node, so the edges form a tree.
+    assert_eq!(visited.len(), maze.edges.len() + 1);
+}
+
+#[test]
+fn test_build_annulus() {
+    let params = Params {
+        radius: 60.0,
+        inner_radius: 20.0,
+        ..Default::default()
+    };
+    let builder = small_builder()
+        .params(params.clone())
+        .algorithm(Algorithm::Kruskal);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert!(maze
+        .nodes
+        .iter()
+        .all(|n| n.point.length() >= params.inner_radius + params.tube_radius * 2.0));
+    let start = maze.nodes[maze.start].point.length();
+    let end = maze.nodes[maze.end].point.length();
+    assert!(end < params.inner_radius + params.min_spacing * 2.0);
+    assert!(start > end);
+}
+
+#[test]
+fn test_get_nearest_k() {
+    let nodes: Vec<Node> = (0..10)
+        .map(|i| Node {
+            point: V2 {
+                x: i as f64,
+                y: 0.0,
+            },
+            index: i,
+        })
+        .collect();
+    let nearest = get_nearest_k(&nodes, nodes[4], 3);
+    let mut indices: Vec<Index> = nearest.iter().map(|n| n.index).collect();
+    indices.sort();
+    assert_eq!(indices, vec![3, 4, 5]);
+}
+
+#[test]
+fn test_render_svg() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let document = render_svg(&maze, &RenderOptions::default());
+    let svg = document.to_string();
+    assert!(svg.starts_with("<svg"));
+    assert_eq!(svg.matches("<path").count(), maze.edges.len());
+    // The layers appear once each, bottom to top.
+    let positions: Vec<usize> = LAYERS
+        .iter()
+        .map(|id| svg.find(&format!("<g id=\"{id}\"")).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_render_svg_clip_path() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let svg = render_svg(&maze, &RenderOptions::default()).to_string();
+    let clip = svg.find("<clipPath id=\"maze-boundary\">").unwrap();
+    assert!(svg[clip..]
+        .starts_with("<clipPath id=\"maze-boundary\">\n<circle cx=\"0\" cy=\"0\" r=\"60\"/>"));
+    let clipped = svg.find("<g clip-path=\"url(#maze-boundary)\">").unwrap();
+    assert!(clipped < svg.find("<g id=\"edges\"").unwrap());
+}
+
+#[test]
+fn test_render_svg_draws_each_node_once() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let svg = render_svg(&maze, &RenderOptions::default()).to_string();
+    let start = svg.find("<g id=\"nodes\">").unwrap();
+    let end = start + svg[start..].find("</g>").unwrap();
+    let circles: Vec<&str> = svg[start..end]
+        .lines()
+        .filter(|line| line.starts_with("<circle"))
+        .collect();
+    let unique: HashSet<&str> = circles.iter().copied().collect();
+    assert_eq!(unique.len(), circles.len());
+    let carved: HashSet<Index> = maze.edges.iter().flat_map(|e| [e.0, e.1]).collect();
+    assert_eq!(circles.len(), carved.len());
+}
+
+#[test]
+fn test_render_svg_walls() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let opts = apply_walls(RenderOptions::default());
+    let svg = render_svg(&maze, &opts).to_string();
+    // Each passage is cut out of the dark boundary in the background color.
+    let start = svg.find("<g id=\"edges\">").unwrap();
+    let end = start + svg[start..].find("</g>").unwrap();
+    let cutouts = svg[start..end]
+        .lines()
+        .filter(|line| line.starts_with("<path") && line.contains("fill=\"white\""))
+        .count();
+    assert_eq!(cutouts, maze.edges.len());
+    assert!(svg.contains("fill=\"#222222\""));
+    assert!(!svg.contains("stroke-width"));
+}
+
+#[test]
+fn test_render_svg_voronoi() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let opts = RenderOptions {
+        voronoi: true,
+        ..Default::default()
+    };
+    let svg = render_svg(&maze, &opts).to_string();
+    let start = svg.find("<g id=\"voronoi\">").unwrap();
+    assert!(start < svg.find("<g id=\"edges\"").unwrap());
+    let end = start + svg[start..].find("</g>").unwrap();
+    let reached = maze.distances().iter().flatten().count();
+    assert_eq!(svg[start..end].matches("<path").count(), reached);
+}
+
+#[test]
+fn test_add_svg_title() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let title = default_svg_title(&maze.metadata);
+    assert_eq!(title, "Maze (algorithm=dfs, layout=grid, seed=42)");
+    let description = default_svg_description(&maze);
+    assert_eq!(
+        description,
+        format!(
+            "A circular maze with {} nodes and {} edges. Start is marked green, end is marked red.",
+            maze.nodes.len(),
+            maze.edges.len()
+        )
+    );
+    let document = render_svg(&maze, &RenderOptions::default());
+    let svg = add_svg_title(document, "Mazes & <more>", &description).to_string();
+    let body = &svg[svg.find('>').unwrap() + 1..];
+    assert!(body.starts_with(&format!(
+        "\n<title>\nMazes &amp; &lt;more&gt;\n</title>\n<desc>\n{description}\n</desc>"
+    )));
+}
+
+#[test]
+fn test_add_scale_bar() {
+    // 120 units across; printed 12 cm wide, that's 10 units a centimeter.
+    assert_eq!(scale_bar_length(121.2, 10.0), 2.0);
+    assert_eq!(scale_bar_length(121.2, 100.0), 0.2);
+    assert_eq!(scale_bar_length(121.2, 1.0), 20.0);
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let opts = RenderOptions {
+        scale_bar: Some(ScaleBar {
+            pixels_per_unit: 10.0,
+            unit: "cm".to_string(),
+        }),
+        ..Default::default()
+    };
+    let svg = add_scale_bar(render_svg(&maze, &RenderOptions::default()), 10.0, "cm").to_string();
+    // Drawn in the annotations layer, as when the render options ask for it.
+    assert_eq!(svg, render_svg(&maze, &opts).to_string());
+    let annotations = &svg[svg.find("<g id=\"annotations\">").unwrap()..];
+    let bar = &annotations[annotations.find("<g id=\"scale-bar\">").unwrap()..];
+    assert_eq!(bar.matches("<line").count(), 4);
+    assert!(bar.contains(">\n2 cm\n</text>"));
+    // 2 cm is 20 units, ending 3% of the width in from the right edge.
+    let attr = |name: &str| -> f64 {
+        let start = bar.find(&format!("{name}=\"")).unwrap() + name.len() + 2;
+        bar[start..start + bar[start..].find('"').unwrap()]
+            .parse()
+            .unwrap()
+    };
+    let right = -60.6 + 121.2 * 0.97;
+    assert!((attr("x1") - (right - 20.0)).abs() < 1e-9);
+    assert!((attr("x2") - right).abs() < 1e-9);
+}
+
+#[test]
+fn test_add_svg_metadata() {
+    let builder = small_builder().algorithm(Algorithm::HuntAndKill);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let document = render_svg(&maze, &RenderOptions::default());
+    let opts = RenderOptions {
+        metadata: true,
+        ..Default::default()
+    };
+    // Either way it goes in the annotations layer.
+    for svg in [
+        add_svg_metadata(document, &maze.metadata).to_string(),
+        render_svg(&maze, &opts).to_string(),
+    ] {
+        assert!(svg.contains(&format!("xmlns:maze=\"{METADATA_NAMESPACE}\"")));
+        let start = svg
+            .find("<g id=\"annotations\">\n<metadata>\n<maze:params ")
+            .unwrap();
+        let params = &svg[start..start + svg[start..].find("/>").unwrap()];
+        for attribute in [
+            "seed=\"42\"",
+            "algorithm=\"hunt-and-kill\"",
+            "layout=\"grid\"",
+            "radius=\"60\"",
+            "tube_radius=\"2.5\"",
+            "min_spacing=\"8.75\"",
+            "timestamp=",
+            &format!("version=\"{}\"", env!("CARGO_PKG_VERSION")),
+        ] {
+            assert!(
+                params.contains(attribute),
+                "{attribute} missing from {params}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_render_svg_color_by_depth() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert_eq!(maze.depths.len(), maze.edges.len());
+    let opts = RenderOptions {
+        color_by: ColorBy::Depth,
+        ..Default::default()
+    };
+    let svg = render_svg(&maze, &opts).to_string();
+    // The first passage takes the start color and the deepest the end color.
+    assert!(svg.contains("stroke=\"#003366\""));
+    assert!(svg.contains("stroke=\"#FFFF00\""));
+    assert!(!svg.contains("stroke=\"white\""));
+}
+
+#[test]
+fn test_render_svg_theme() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let svg = render_svg(&maze, &apply_theme(Theme::Parchment)).to_string();
+    assert!(svg.contains("background-color: #F5DEB3"));
+    assert!(svg.contains("stroke=\"#5C4033\""));
+    assert!(svg.contains("fill=\"#2E8B57\""));
+    assert!(!svg.contains("white"));
+}
+
+#[test]
+fn test_render_svg_curved() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let path = solve(Solver::Bfs, &maze.edges, &maze.nodes, maze.start, maze.end).unwrap();
+    let opts = RenderOptions {
+        curvature: 0.3,
+        ..Default::default()
+    };
+    let svg = render_svg_solved(&maze, &path, &opts).to_string();
+    assert_eq!(svg.matches(" Q").count(), maze.edges.len() + path.len() - 1);
+    let control = compute_control_point(V2 { x: 0.0, y: 0.0 }, V2 { x: 10.0, y: 0.0 }, 0.3);
+    assert_eq!(control, V2 { x: 5.0, y: 3.0 });
+}
+
+#[test]
+fn test_render_svg_taper() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let opts = RenderOptions {
+        taper: true,
+        ..Default::default()
+    };
+    let svg = render_svg(&maze, &opts).to_string();
+    assert_eq!(svg.matches(" A").count(), 2 * maze.edges.len());
+    assert!(!svg.contains("stroke=\"white\""));
+    let data = tapered_path_data(V2 { x: 0.0, y: 0.0 }, V2 { x: 10.0, y: 0.0 }, 1.0, 0.5);
+    let path = svg::node::element::Path::new().set("d", data).to_string();
+    assert!(path.contains("d=\"M0,1 L10,0.5 A0.5,0.5,0,0,0,10,-0.5 L0,-1 A1,1,0,0,0,0,1 z\""));
+}
+
+#[test]
+fn test_render_svg_animate_solve() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let path = solve(Solver::Bfs, &maze.edges, &maze.nodes, maze.start, maze.end).unwrap();
+    let opts = RenderOptions {
+        animate_solve: Some(4.0),
+        ..Default::default()
+    };
+    let svg = render_svg_solved(&maze, &path, &opts).to_string();
+    assert!(svg.contains("@keyframes reveal"));
+    assert_eq!(svg.matches("animation: reveal").count(), path.len() - 1);
+    // The first segment starts straight away.
+    assert!(svg.contains("linear 0s forwards"));
+    let curve = quadratic_length(
+        V2 { x: 0.0, y: 0.0 },
+        V2 { x: 5.0, y: 0.0 },
+        V2 { x: 10.0, y: 0.0 },
+    );
+    assert!((curve - 10.0).abs() < 1e-9);
+    // A parabola with a known arc length.
+    let curve = quadratic_length(
+        V2 { x: -1.0, y: 1.0 },
+        V2 { x: 0.0, y: -1.0 },
+        V2 { x: 1.0, y: 1.0 },
+    );
+    assert!((curve - 2.957885715).abs() < 1e-6, "{curve}");
+}
+
+#[test]
+fn test_export_png() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let svg = render_svg(&maze, &RenderOptions::default()).to_string();
+    // The 60 radius maze is 121.2 units square with padding.
+    for (dpi, side) in [(96.0, 121), (192.0, 242)] {
+        let png = export_png(&svg, dpi).unwrap();
+        let image = resvg::tiny_skia::Pixmap::decode_png(&png).unwrap();
+        assert_eq!((image.width(), image.height()), (side, side));
+    }
+    assert!(export_png("<svg", 96.0).is_err());
+}
+
+#[test]
+fn test_save_svgz() {
+    use std::io::Read;
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let document = render_svg(&maze, &RenderOptions::default());
+    let path = std::env::temp_dir().join(format!("maze-test-{}.svgz", std::process::id()));
+    save_svgz(&document, &path).unwrap();
+    let compressed = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let mut svg = String::new();
+    flate2::read::GzDecoder::new(&compressed[..])
+        .read_to_string(&mut svg)
+        .unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg, document.to_string());
+    assert!(compressed.len() * 4 < svg.len());
+}
+
+#[test]
+fn test_write_output_all() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let document = render_svg(&maze, &RenderOptions::default());
+    let dir = std::env::temp_dir().join(format!("maze-formats-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let base = dir.join("maze.svg");
+    write_output(&maze, &document, OutputFormat::All, &base).unwrap();
+    let mut names: Vec<String> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "maze.dot",
+            "maze.edgelist",
+            "maze.json",
+            "maze.png",
+            "maze.svg",
+            "maze.svgz",
+            "maze.tex",
+            "maze.txt"
+        ]
+    );
+    assert_eq!(load_maze(&dir.join("maze.json")).unwrap().edges, maze.edges);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        OutputFormat::expand(&[OutputFormat::Png, OutputFormat::All, OutputFormat::Svg]),
+        [
+            OutputFormat::Png,
+            OutputFormat::Svg,
+            OutputFormat::Svgz,
+            OutputFormat::Dot,
+            OutputFormat::Json,
+            OutputFormat::Ascii,
+            OutputFormat::EdgeList,
+            OutputFormat::Tikz,
+        ]
+    );
+}
+
+#[test]
+fn test_render_svg_solved() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let path = solve(Solver::Bfs, &maze.edges, &maze.nodes, maze.start, maze.end).unwrap();
+    let svg = render_svg_solved(&maze, &path, &RenderOptions::default()).to_string();
+    assert_eq!(
+        svg.matches("<path").count(),
+        maze.edges.len() + path.len() - 1
+    );
+    // The solution is drawn over the passages and under the markers.
+    let group = svg.find("<g id=\"solution\">").unwrap();
+    assert!(svg.rfind("stroke=\"white\"").unwrap() < group);
+    assert!(svg.find("fill=\"green\"").unwrap() > group);
+}
+
+#[test]
+fn test_same_seed_same_maze() {
+    let builder = small_builder().seed(1234);
+    let a = builder.build(&mut builder.rng()).unwrap();
+    let b = builder.build(&mut builder.rng()).unwrap();
+    assert_eq!(a.edges, b.edges);
+    assert_eq!(a.start, b.start);
+    assert_eq!(a.end, b.end);
+    let opts = RenderOptions::default();
+    assert_eq!(
+        render_svg(&a, &opts).to_string(),
+        render_svg(&b, &opts).to_string()
+    );
+}
+
+#[test]
+fn test_build_batch_matches_sequential() {
+    let builder = small_builder().layout(NodeLayout::Random).target_nodes(150);
+    let seeds: Vec<u64> = (100..106).collect();
+    let batch = builder.build_batch(&seeds, 4).unwrap();
+    assert_eq!(batch.len(), seeds.len());
+    for ((seed, maze), &expected) in batch.iter().zip(&seeds) {
+        assert_eq!(*seed, expected);
+        let single = builder.clone().seed(expected);
+        let single = single.build(&mut single.rng()).unwrap();
+        assert_eq!(export_json(maze), export_json(&single));
+    }
+}
+
+#[test]
+fn test_dfs_deep_maze_on_small_stack() {
+    // The recursive dfs used one stack frame per level of depth, which
+    // overflowed this stack long before the maze was complete.
+    let builder = MazeBuilder::new()
+        .params(Params {
+            radius: 200.0,
+            ..Default::default()
+        })
+        .seed(42);
+    let maze = std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(move || builder.build(&mut builder.rng()).unwrap())
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(maze.edges.len() > 1000);
+}
+
+#[test]
+fn test_json_round_trip() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let value = export_json(&maze);
+    let text = serde_json::to_string(&value).unwrap();
+    let copy = import_json(&serde_json::from_str(&text).unwrap()).unwrap();
+    assert_eq!(copy.edges, maze.edges);
+    assert_eq!(copy.start, maze.start);
+    assert_eq!(copy.end, maze.end);
+    assert_eq!(copy.params.radius, maze.params.radius);
+    assert_eq!(copy.params.tube_radius, maze.params.tube_radius);
+    assert_eq!(copy.nodes.len(), maze.nodes.len());
+    for (a, b) in copy.nodes.iter().zip(&maze.nodes) {
+        assert_eq!(a.index, b.index);
+        assert_eq!(a.point, b.point);
+    }
+    assert_eq!(export_json(&copy), value);
+}
+
+#[test]
+fn test_json_keeps_boundary() {
+    let point = |x, y| V2 { x, y };
+    for boundary in [
+        BoundaryShape::Rectangle {
+            width: 120.0,
+            height: 80.0,
+        },
+        BoundaryShape::Polygon {
+            vertices: vec![point(-60.0, 50.0), point(60.0, 50.0), point(0.0, -60.0)],
+        },
+    ] {
+        let params = Params {
+            radius: 60.0,
+            inner_radius: 15.0,
+            boundary: Some(boundary),
+            ..Default::default()
+        };
+        let builder = small_builder().params(params.clone());
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        let copy = import_json(&export_json(&maze)).unwrap();
+        assert_eq!(copy.params.boundary, params.boundary);
+        assert_eq!(copy.params.inner_radius, params.inner_radius);
+    }
+    // A full disk writes neither, as older files don't.
+    let builder = small_builder();
+    let value = export_json(&builder.build(&mut builder.rng()).unwrap());
+    assert!(value.get("boundary").is_none());
+    assert!(value.get("inner_radius").is_none());
+    assert_eq!(import_json(&value).unwrap().params.boundary, None);
+}
+
+#[test]
+fn test_save_load_maze() {
+    let builder = small_builder().algorithm(Algorithm::Prim).braid(0.5);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let path = std::env::temp_dir().join(format!("maze-{}.json", std::process::id()));
+    save_maze(&maze, &path).unwrap();
+    let copy = load_maze(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(copy.edges, maze.edges);
+    assert_eq!(copy.depths, maze.depths);
+    assert_eq!(copy.metadata, maze.metadata);
+}
+
+#[test]
+fn test_load_maze_fixture() {
+    let maze = load_maze(std::path::Path::new("tests/fixtures/small_maze.json")).unwrap();
+    assert_eq!(maze.nodes.len(), 16);
+    assert_eq!(
+        maze.sorted_edges(),
+        [
+            Edge(0, 6),
+            Edge(6, 10),
+            Edge(7, 8),
+            Edge(8, 0),
+            Edge(9, 15),
+            Edge(10, 9),
+            Edge(10, 14),
+        ]
+    );
+    // Files from before the seed and algorithm were stored still load.
+    assert_eq!((maze.metadata.seed, maze.metadata.algorithm), (None, None));
+    assert_eq!(maze.metadata.node_count, 16);
+}
+
+#[test]
+fn test_expand_output_template() {
+    let builder = small_builder().algorithm(Algorithm::HuntAndKill).seed(42);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let path = expand_output_template("out/{algorithm}-{seed}-{nodes}.svg", &maze.metadata);
+    assert_eq!(
+        path,
+        std::path::PathBuf::from(format!("out/hunt-and-kill-42-{}.svg", maze.nodes.len()))
+    );
+    let path = expand_output_template("image-{timestamp}.svg", &maze.metadata);
+    let name = path.to_str().unwrap();
+    let timestamp = &name["image-".len()..name.len() - ".svg".len()];
+    assert!(timestamp.parse::<u64>().unwrap() > 0);
+}
+
+#[test]
+fn test_pol_and_v2_serde() {
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct Marker {
+        #[serde(with = "maze::v2_serde")]
+        at: V2,
+        pol: Pol,
+    }
+    let marker = Marker {
+        at: V2 { x: 1.5, y: -2.0 },
+        pol: Pol { a: 0.25, r: 3.0 },
+    };
+    let text = serde_json::to_string(&marker).unwrap();
+    assert_eq!(
+        text,
+        r#"{"at":{"x":1.5,"y":-2.0},"pol":{"a":0.25,"r":3.0}}"#
+    );
+    let copy: Marker = serde_json::from_str(&text).unwrap();
+    assert_eq!(copy.at, marker.at);
+    assert_eq!((copy.pol.a, copy.pol.r), (0.25, 3.0));
+}
+
+#[test]
+fn test_export_dot() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let mut out = Vec::new();
+    export_dot(&maze, &mut out).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = dot.lines().collect();
+    assert_eq!(lines.len(), maze.nodes.len() + maze.edges.len() + 2);
+    assert_eq!(lines[0], "graph maze {");
+    assert_eq!(*lines.last().unwrap(), "}");
+    for line in &lines[1..lines.len() - 1] {
+        assert!(line.starts_with("  ") && line.ends_with(';'), "{line}");
+    }
+    assert_eq!(dot.matches(" -- ").count(), maze.edges.len());
+    assert!(dot.contains(&format!("  {} [label=", maze.start)));
+    assert_eq!(dot.matches("color=green").count(), 1);
+    assert_eq!(dot.matches("color=red").count(), 1);
+}
+
+#[test]
+fn test_export_tikz() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let mut out = Vec::new();
+    export_tikz(&maze, &mut out, 10.0).unwrap();
+    let tikz = String::from_utf8(out).unwrap();
+    let mut depth = 0i32;
+    for c in tikz.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {}
+        }
+        assert!(depth >= 0, "{tikz}");
+    }
+    assert_eq!(depth, 0);
+    let lines: Vec<&str> = tikz.lines().filter(|l| !l.starts_with('%')).collect();
+    assert_eq!(lines[0], "\\begin{tikzpicture}");
+    assert_eq!(*lines.last().unwrap(), "\\end{tikzpicture}");
+    for line in &lines[1..lines.len() - 1] {
+        assert!(
+            line.starts_with("\\")
+                && (line.ends_with(';') || line.ends_with('}') || line.ends_with(']')),
+            "{line}"
+        );
+    }
+    assert_eq!(tikz.matches("\\draw ").count(), maze.edges.len());
+    assert!(tikz.contains("\\definecolor{mazepath}{HTML}{FFFFFF}"));
+    assert!(tikz.contains("\\fill[mazestart]"));
+
+    let mut out = Vec::new();
+    export_tikz_with_options(&maze, &mut out, 10.0, &apply_theme(Theme::Inverted)).unwrap();
+    let tikz = String::from_utf8(out).unwrap();
+    assert!(tikz.contains("\\definecolor{mazepath}{HTML}{000000}"));
+    assert!(tikz.contains("\\definecolor{mazestart}{HTML}{008000}"));
+}
+
+#[test]
+fn test_edge_list_round_trip() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let mut out = Vec::new();
+    export_edge_list(&maze, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert_eq!(
+        text.lines().next().unwrap(),
+        format!(
+            "# nodes={} edges={} radius=60 tube_radius=2.5 start={} end={}",
+            maze.nodes.len(),
+            maze.edges.len(),
+            maze.start,
+            maze.end
+        )
+    );
+    let copy = import_edge_list(&mut text.as_bytes()).unwrap();
+    assert_eq!(copy.edges, maze.edges);
+    assert_eq!((copy.start, copy.end), (maze.start, maze.end));
+    assert_eq!(copy.params.radius, maze.params.radius);
+    assert_eq!(copy.params.tube_radius, maze.params.tube_radius);
+    for (a, b) in copy.nodes.iter().zip(&maze.nodes) {
+        assert_eq!(a.point, b.point);
+    }
+    assert_eq!(copy.nodes.len(), maze.nodes.len());
+    // Drop a passage without fixing the header.
+    let short: String = text
+        .lines()
+        .take(text.lines().count() - 1)
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(matches!(
+        import_edge_list(&mut short.as_bytes()),
+        Err(MazeError::ParseError { .. })
+    ));
+}
+
+#[test]
+fn test_render_ascii() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let ascii = render_ascii(&maze, 80, 40);
+    let rows: Vec<&str> = ascii.lines().collect();
+    assert_eq!(rows.len(), 40);
+    assert!(rows.iter().all(|row| row.chars().count() == 80));
+    assert!(ascii.contains('O'));
+    assert!(ascii
+        .chars()
+        .all(|c| matches!(c, '.' | 'O' | '-' | '|' | '/' | '\\' | '\n')));
+}
+
+#[test]
+fn test_render_ascii_tiny() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert_eq!(render_ascii(&maze, 0, 2), "\n\n");
+    assert_eq!(render_ascii(&maze, 1, 1), "O\n");
+}
+
+#[test]
+fn test_build_rect() {
+    let params = Params {
+        radius: 60.0,
+        boundary: Some(BoundaryShape::Rectangle {
+            width: 160.0,
+            height: 80.0,
+        }),
+        ..Default::default()
+    };
+    let builder = small_builder().params(params);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert!(maze
+        .nodes
+        .iter()
+        .all(|n| n.point.x.abs() <= 80.0 && n.point.y.abs() <= 40.0));
+    let start = maze.nodes[maze.start].point;
+    let end = maze.nodes[maze.end].point;
+    assert!(start.x < 0.0 && start.y < 0.0);
+    assert!(end.x > 0.0 && end.y > 0.0);
+    let svg = render_svg(&maze, &RenderOptions::default()).to_string();
+    assert!(svg.contains("<rect"));
+}
+
+#[test]
+fn test_build_polygon() {
+    let point = |x, y| V2 { x, y };
+    let shape = BoundaryShape::Polygon {
+        vertices: vec![point(-60.0, 50.0), point(60.0, 50.0), point(0.0, -60.0)],
+    };
+    let params = Params {
+        radius: 60.0,
+        boundary: Some(shape.clone()),
+        ..Default::default()
+    };
+    for layout in [NodeLayout::Grid, NodeLayout::Hex, NodeLayout::Poisson] {
+        let builder = small_builder().params(params.clone()).layout(layout);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        assert!(maze.nodes.len() > 30);
+        assert!(maze
+            .nodes
+            .iter()
+            .all(|n| shape.contains(n.point, params.tube_radius * 2.0)));
+        let svg = render_svg(&maze, &RenderOptions::default()).to_string();
+        // The outline is drawn once and used again as the clip path.
+        assert_eq!(svg.matches("<path").count(), maze.edges.len() + 2);
+    }
+}
+
+#[test]
+fn test_builder_setters() {
+    let builder = MazeBuilder::new()
+        .radius(80.0)
+        .tube_radius(2.0)
+        .neighbor_k(8)
+        .angle_cutoff(0.7)
+        .inner_radius(20.0)
+        .braid(0.5)
+        .seed(9);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert_eq!(
+        maze.metadata,
+        MazeMeta {
+            radius: 80.0,
+            tube_radius: 2.0,
+            seed: Some(9),
+            algorithm: Some(Algorithm::Dfs),
+            layout: Some(NodeLayout::Grid),
+            min_spacing: Some(maze.params.min_spacing),
+            node_count: maze.nodes.len(),
+        }
+    );
+    assert!(maze.nodes.iter().all(|n| n.point.length() > 20.0));
+}
+
+#[test]
+fn test_build_rejects_bad_params() {
+    for builder in [
+        MazeBuilder::new().radius(-1.0),
+        MazeBuilder::new().tube_radius(0.0),
+        MazeBuilder::new().neighbor_k(0),
+        MazeBuilder::new().inner_radius(600.0),
+        MazeBuilder::new().braid(1.5),
+    ] {
+        assert!(builder.build(&mut builder.rng()).is_err());
+    }
+}
+
+#[test]
+fn test_build_rejects_empty_boundary() {
+    let point = |x, y| V2 { x, y };
+    // A sliver between grid points.
+    let builder = MazeBuilder::new().boundary(BoundaryShape::Polygon {
+        vertices: vec![point(0.1, 0.1), point(0.2, 0.1), point(0.1, 0.2)],
+    });
+    assert!(matches!(
+        builder.build(&mut builder.rng()),
+        Err(MazeError::NoNodes)
+    ));
+}
+
+#[test]
+fn test_csv_layout() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/nodes.csv");
+    let rows = std::fs::read_to_string(path).unwrap().lines().count() - 1;
+    let builder = MazeBuilder::new()
+        .radius(60.0)
+        .layout(NodeLayout::Csv)
+        .layout_file(path)
+        .seed(1);
+    let nodes = builder.gen_nodes(&mut builder.rng()).unwrap();
+    assert_eq!(nodes.len(), rows);
+    assert!(builder.build(&mut builder.rng()).unwrap().edges.len() > rows / 2);
+
+    // Points outside the boundary or crowding an earlier one are dropped.
+    let crowded = std::env::temp_dir().join(format!("maze-nodes-{}.csv", std::process::id()));
+    std::fs::write(&crowded, "0,0\n1,0\n100,0\n20,0\n").unwrap();
+    let builder = builder.layout_file(&crowded);
+    let nodes = builder.gen_nodes(&mut builder.rng());
+    std::fs::write(&crowded, "0,0\nzero,0\n").unwrap();
+    let bad = builder.build(&mut builder.rng());
+    std::fs::remove_file(&crowded).unwrap();
+    let points: Vec<(f64, f64)> = nodes
+        .unwrap()
+        .iter()
+        .map(|n| (n.point.x, n.point.y))
+        .collect();
+    assert_eq!(points, [(0.0, 0.0), (20.0, 0.0)]);
+    assert!(matches!(bad, Err(MazeError::ParseError { .. })));
+}
+
+#[test]
+fn test_mask_layout() {
+    // Two white squares on black, 50 x 50 pixels.
+    let white =
+        |x: u32, y: u32| (5..20).contains(&y) && ((5..20).contains(&x) || (30..45).contains(&x));
+    let mask = image::GrayImage::from_fn(50, 50, |x, y| {
+        image::Luma([if white(x, y) { 255 } else { 0 }])
+    });
+    let path = std::env::temp_dir().join(format!("maze-mask-{}.png", std::process::id()));
+    mask.save(&path).unwrap();
+    let builder = MazeBuilder::new()
+        .params(Params {
+            radius: 50.0,
+            tube_radius: 0.5,
+            min_spacing: 3.0,
+            ..Default::default()
+        })
+        .layout(NodeLayout::Mask)
+        .layout_file(&path)
+        .seed(2);
+    let nodes = builder.gen_nodes(&mut builder.rng());
+    std::fs::remove_file(&path).unwrap();
+    let nodes = nodes.unwrap();
+    // The image spans the boundary's 100 x 100 box, 2 units to a pixel.
+    let pixel = |n: &Node| {
+        (
+            (n.point.x / 2.0 + 25.0) as u32,
+            (n.point.y / 2.0 + 25.0) as u32,
+        )
+    };
+    assert!(nodes.iter().all(|n| white(pixel(n).0, pixel(n).1)));
+    let left = nodes.iter().filter(|n| n.point.x < 0.0).count();
+    assert!(
+        left > 20 && nodes.len() - left > 20,
+        "{left} of {}",
+        nodes.len()
+    );
+    for (i, a) in nodes.iter().enumerate() {
+        assert_eq!(a.index, i);
+        assert!(nodes[i + 1..]
+            .iter()
+            .all(|b| (a.point - b.point).length() >= 3.0));
+    }
+}
+
+#[test]
+fn test_maze_report() {
+    let builder = small_builder().seed(4);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let report = maze_report(&maze, Duration::from_millis(12), 345);
+    assert_eq!(report.seed, 4);
+    assert_eq!(report.algorithm, "dfs");
+    assert_eq!(report.layout, "grid");
+    assert_eq!(report.node_count, maze.nodes.len());
+    assert_eq!(report.edge_count, maze.edges.len());
+    let analysis = analyze_maze(&maze.edges, &maze.nodes, maze.start, maze.end);
+    assert_eq!(report.dead_end_count, analysis.dead_end_count);
+    assert_eq!(report.solution_hops, Some(analysis.solution_hops));
+    // Each passage adds to the degree of both its ends.
+    let degree_sum = report.avg_degree * report.node_count as f64;
+    assert!((degree_sum - 2.0 * report.edge_count as f64).abs() < 1e-9);
+    assert!(report.diameter >= analysis.solution_hops);
+    assert_eq!((report.generation_time_ms, report.svg_bytes), (12, 345));
+}
+
+// Regenerate with UPDATE_GOLDEN=1 cargo test.
+#[test]
+fn test_debug_golden() {
+    let builder = MazeBuilder::new().radius(30.0).seed(7);
+    let actual = format!("{:#?}\n", builder.build(&mut builder.rng()).unwrap());
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/maze_debug.txt");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(path, &actual).unwrap();
+    }
+    assert_eq!(actual, std::fs::read_to_string(path).unwrap());
+}
+
+#[test]
+fn test_display() {
+    let builder = small_builder();
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let text = maze.to_string();
+    assert!(text.starts_with(&format!(
+        "nodes: {}\nedges: {}\n",
+        maze.nodes.len(),
+        maze.edges.len()
+    )));
+    assert!(text.contains(&format!("depth: {}", maze.depth())));
+}
+
+#[derive(Default)]
+struct Recorder {
+    placed: Vec<(usize, bool)>,
+    nodes: usize,
+    visited: Vec<Index>,
+    edges: Vec<Edge>,
+    complete: bool,
+}
+
+impl MazeObserver for Recorder {
+    fn on_nodes_placed(&mut self, count: usize, done: bool) {
+        self.placed.push((count, done));
+    }
+
+    fn on_node_added(&mut self, _node: &Node) {
+        self.nodes += 1;
+    }
+
+    fn on_node_visited(&mut self, node: &Node, _depth: usize) {
+        self.visited.push(node.index);
+    }
+
+    fn on_edge_added(&mut self, edge: Edge, _nodes: &[Node]) {
+        self.edges.push(edge);
+    }
+
+    fn on_complete(&mut self, _maze: &Maze) {
+        self.complete = true;
+    }
+}
+
+#[test]
+fn test_build_with_observer() {
+    for builder in [
+        small_builder(),
+        small_builder().algorithm(Algorithm::Prim).braid(0.5),
+    ] {
+        let mut recorder = Recorder::default();
+        let maze = builder
+            .build_with_observer(&mut builder.rng(), &mut recorder)
+            .unwrap();
+        assert_eq!(recorder.nodes, maze.nodes.len());
+        assert_eq!(recorder.edges.len(), maze.edges.len());
+        assert_eq!(
+            recorder.edges.iter().cloned().collect::<HashSet<Edge>>(),
+            maze.edges
+        );
+        // Every node the maze reaches is visited exactly once.
+        let reached: HashSet<Index> = maze
+            .distances()
+            .iter()
+            .enumerate()
+            .filter(|(_, d)| d.is_some())
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(recorder.visited.len(), reached.len());
+        assert_eq!(
+            recorder.visited.iter().copied().collect::<HashSet<Index>>(),
+            reached
+        );
+        assert_eq!(recorder.visited[0], maze.start);
+        assert!(recorder.complete);
+    }
+}
+
+#[test]
+fn test_build_reports_phases() {
+    let builder = small_builder().braid(0.5);
+    let mut timing = PhaseTiming::new();
+    builder
+        .build_with_observer(&mut builder.rng(), &mut timing)
+        .unwrap();
+    let phases: Vec<&str> = timing.phases.iter().map(|(p, _)| p.as_str()).collect();
+    assert_eq!(
+        phases,
+        [
+            "Node generation",
+            "Neighbor cache",
+            "DFS traversal",
+            "Braid and cycles",
+            "Endpoints and depths"
+        ]
+    );
+    assert_eq!(timing.table().lines().count(), phases.len() + 1);
+}
+
+#[test]
+fn test_random_layout_reports_placement() {
+    let builder = small_builder().layout(NodeLayout::Random).target_nodes(40);
+    let mut recorder = Recorder::default();
+    let maze = builder
+        .build_with_observer(&mut builder.rng(), &mut recorder)
+        .unwrap();
+    assert_eq!(maze.nodes.len(), 40);
+    let mut expected: Vec<(usize, bool)> = (1..=40).map(|n| (n, false)).collect();
+    expected.push((40, true));
+    assert_eq!(recorder.placed, expected);
+}
+
+#[test]
+fn test_every_algorithm_carves_a_tree() {
+    for algorithm in [
+        Algorithm::Dfs,
+        Algorithm::Bfs,
+        Algorithm::Wilson,
+        Algorithm::Prim,
+        Algorithm::Kruskal,
+        Algorithm::AldousBroder,
+        Algorithm::HuntAndKill,
+        Algorithm::GrowingTree,
+        Algorithm::RecursiveDivision,
+    ] {
+        let builder = small_builder().algorithm(algorithm);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        assert_eq!(verify_maze(&maze), Ok(()), "{algorithm:?}");
+    }
+}
+
+#[test]
+fn test_verify_maze_rejects_braid() {
+    let builder = small_builder().braid(1.0);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    assert!(matches!(
+        verify_maze(&maze),
+        Err(ValidationError::EdgeCount { .. })
+    ));
+}
+
+#[test]
+fn test_start_and_end_placement() {
+    let builder = small_builder()
+        .start_at(Placement::Top)
+        .end_at(Placement::Farthest);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let start = maze.nodes[maze.start].point;
+    assert!(maze.nodes.iter().all(|n| n.point.y >= start.y));
+    let hops = maze.distances();
+    assert_eq!(hops[maze.end], hops.iter().flatten().max().copied());
+}
+
+// Mean turn, in radians, between consecutive passages on the solution.
+fn mean_solution_turn(maze: &Maze) -> f64 {
+    let path = solve_bfs(&maze.edges, &maze.nodes, maze.start, maze.end).unwrap();
+    let point = |i: Index| maze.nodes[i].point;
+    let turns: Vec<f64> = path
+        .windows(3)
+        .map(|w| {
+            radian_diff(
+                (point(w[1]) - point(w[0])).angle(),
+                (point(w[2]) - point(w[1])).angle(),
+            )
+        })
+        .collect();
+    turns.iter().sum::<f64>() / turns.len() as f64
+}
+
+#[test]
+fn test_angle_bias() {
+    let turn = |bias| {
+        let builder = small_builder().radius(100.0).angle_bias(bias);
+        mean_solution_turn(&builder.build(&mut builder.rng()).unwrap())
+    };
+    let none = turn(AngleBias::None);
+    let soft = turn(AngleBias::Soft { strength: 4.0 });
+    assert!(soft < none);
+    assert_eq!("soft:2.5".parse(), Ok(AngleBias::Soft { strength: 2.5 }));
+    assert_eq!("hard:1".parse(), Ok(AngleBias::Hard { cutoff: 1.0 }));
+    assert!("soft".parse::<AngleBias>().is_err());
+}
+
+#[test]
+fn test_delaunay_connectivity() {
+    let gabriel = small_builder()
+        .layout(NodeLayout::Random)
+        .target_nodes(300)
+        .connectivity(Connectivity::Gabriel);
+    let maze = gabriel.build(&mut gabriel.rng()).unwrap();
+    assert_eq!(verify_maze(&maze), Ok(()));
+    let neighbors = gabriel_neighbors(&maze.nodes);
+    assert!(maze
+        .edges
+        .iter()
+        .all(|&Edge(a, b)| neighbors[&a].contains(&b)));
+    for algorithm in [Algorithm::Dfs, Algorithm::Bfs, Algorithm::GrowingTree] {
+        let builder = small_builder()
+            .layout(NodeLayout::Random)
+            .target_nodes(300)
+            .connectivity(Connectivity::Delaunay)
+            .algorithm(algorithm);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        assert_eq!(verify_maze(&maze), Ok(()), "{algorithm:?}");
+        // Every passage is an edge of the triangulation.
+        let neighbors = delaunay_neighbors(&maze.nodes);
+        assert!(maze
+            .edges
+            .iter()
+            .all(|&Edge(a, b)| neighbors[&a].contains(&b)));
+    }
+}
+
+#[test]
+fn test_edge_length_limits() {
+    for algorithm in [Algorithm::Dfs, Algorithm::Bfs, Algorithm::GrowingTree] {
+        // Nodes are at least 8.75 apart.
+        let builder = small_builder()
+            .radius(100.0)
+            .layout(NodeLayout::Random)
+            .min_edge_length(9.0)
+            .max_edge_length(16.0)
+            .algorithm(algorithm);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        assert!(maze.edges.len() > 150, "{algorithm:?}");
+        assert!(maze.edges.iter().all(|&Edge(a, b)| {
+            let length = (maze.nodes[a].point - maze.nodes[b].point).length();
+            (9.0..=16.0).contains(&length)
+        }));
+    }
+    let builder = small_builder().min_edge_length(3.0).max_edge_length(2.0);
+    assert!(matches!(
+        builder.build(&mut builder.rng()),
+        Err(MazeError::InvalidParams { .. })
+    ));
+}
+
+#[test]
+fn test_max_degree() {
+    for (algorithm, max_degree) in [
+        (Algorithm::Dfs, 2),
+        (Algorithm::Bfs, 2),
+        (Algorithm::GrowingTree, 2),
+        (Algorithm::Dfs, 3),
+    ] {
+        let builder = small_builder().algorithm(algorithm).max_degree(max_degree);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        let graph = MazeGraph::from_edges(&maze.edges, maze.nodes.len());
+        assert!(maze.edges.len() > 1, "{algorithm:?}");
+        assert!((0..maze.nodes.len()).all(|i| graph.degree(i) <= max_degree));
+    }
+    // A single strand has exactly two ends.
+    let builder = small_builder().max_degree(2);
+    let maze = builder.build(&mut builder.rng()).unwrap();
+    let graph = MazeGraph::from_edges(&maze.edges, maze.nodes.len());
+    let ends = (0..maze.nodes.len()).filter(|&i| graph.degree(i) == 1);
+    assert_eq!(ends.count(), 2);
+}
+
+#[test]
+fn test_loop_erase() {
+    let build = |loop_erase| {
+        let builder = small_builder().radius(100.0).loop_erase(loop_erase);
+        builder.build(&mut builder.rng()).unwrap()
+    };
+    let (plain, erased) = (build(false), build(true));
+    assert_eq!(verify_maze(&erased), Ok(()));
+    assert!(erased.edges.len() * 10 >= plain.edges.len() * 9);
+    // Random walks branch far more than one long search.
+    let dead_ends =
+        |maze: &Maze| analyze_maze(&maze.edges, &maze.nodes, maze.start, maze.end).dead_end_count;
+    assert!(dead_ends(&erased) > dead_ends(&plain));
+}
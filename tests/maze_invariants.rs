@@ -0,0 +1,115 @@
+use maze::*;
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+// The layouts that stop at `target_nodes`, which keeps these mazes small.
+const LAYOUTS: [NodeLayout; 4] = [
+    NodeLayout::Grid,
+    NodeLayout::Spiral,
+    NodeLayout::Random,
+    NodeLayout::Halton,
+];
+
+const ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::Dfs,
+    Algorithm::Bfs,
+    Algorithm::Wilson,
+    Algorithm::Prim,
+    Algorithm::Kruskal,
+    Algorithm::AldousBroder,
+    Algorithm::HuntAndKill,
+    Algorithm::GrowingTree,
+    Algorithm::RecursiveDivision,
+];
+
+// The first invariant `maze` breaks, if any. Like `verify_maze`, the tree
+// checks cover only the carved nodes: the start and every node with a
+// passage.
+fn check_invariants(maze: &Maze) -> std::result::Result<(), String> {
+    let params = &maze.params;
+    let carved: HashSet<Index> = maze
+        .edges
+        .iter()
+        .flat_map(|e| [e.0, e.1])
+        .chain([maze.start])
+        .collect();
+    if maze.edges.len() + 1 != carved.len() {
+        return Err(format!(
+            "spanning tree: {} edges for {} carved nodes",
+            maze.edges.len(),
+            carved.len()
+        ));
+    }
+    if let Some(Edge(a, _)) = maze.edges.iter().find(|e| e.0 == e.1) {
+        return Err(format!("self-loop: node {a} has a passage to itself"));
+    }
+    let hops = maze.distances();
+    if let Some(i) = carved.iter().find(|&&i| hops[i].is_none()) {
+        return Err(format!(
+            "reachability: node {i} can't be reached from the start"
+        ));
+    }
+    let edges = maze.sorted_edges();
+    for (i, &edge) in edges.iter().enumerate() {
+        for &other in &edges[i + 1..] {
+            let others: HashSet<Edge> = [other].into();
+            if edge_intersects(params, edge, &others, &maze.nodes) {
+                return Err(format!(
+                    "crossing: edge {}-{} crosses edge {}-{}",
+                    edge.0, edge.1, other.0, other.1
+                ));
+            }
+        }
+    }
+    let boundary = params.boundary();
+    if let Some(node) = maze.nodes.iter().find(|n| !boundary.contains(n.point, 0.0)) {
+        return Err(format!(
+            "boundary: node {} at ({}, {}) is outside",
+            node.index, node.point.x, node.point.y
+        ));
+    }
+    for (i, a) in maze.nodes.iter().enumerate() {
+        for b in &maze.nodes[i + 1..] {
+            let distance = (a.point - b.point).length();
+            if distance < params.min_spacing * 0.99 {
+                return Err(format!(
+                    "spacing: nodes {} and {} are {distance} apart",
+                    a.index, b.index
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn maze_invariants(
+        seed in any::<u64>(),
+        nodes in 10..=50usize,
+        layout in 0..LAYOUTS.len(),
+        algorithm in 0..ALGORITHMS.len(),
+    ) {
+        let builder = MazeBuilder::new()
+            .radius(60.0)
+            .target_nodes(nodes)
+            .layout(LAYOUTS[layout])
+            .algorithm(ALGORITHMS[algorithm])
+            .seed(seed);
+        let maze = builder.build(&mut builder.rng()).unwrap();
+        prop_assert!(maze.nodes.len() <= nodes);
+        let algorithm = ALGORITHMS[algorithm];
+        if let Err(broken) = check_invariants(&maze) {
+            prop_assert!(
+                false,
+                "{:?} on {:?} with seed {}: {}",
+                algorithm,
+                LAYOUTS[layout],
+                seed,
+                broken
+            );
+        }
+    }
+}
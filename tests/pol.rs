@@ -0,0 +1,33 @@
+use maze::{radian_diff, Pol, V2};
+use proptest::prelude::*;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+proptest! {
+    #[test]
+    fn round_trip(a in -1000.0..1000.0f64, r in 1e-6..1e3f64) {
+        let result = Pol::from(V2::from(Pol { a, r }));
+        prop_assert!(radian_diff(result.a, a) < 1e-10, "{} became {}", a, result.a);
+        prop_assert!((result.r - r).abs() < 1e-10, "{} became {}", r, result.r);
+    }
+}
+
+#[test]
+fn test_pol_arithmetic() {
+    let (p, q) = (
+        Pol {
+            a: FRAC_PI_2,
+            r: 2.0,
+        },
+        Pol {
+            a: FRAC_PI_2,
+            r: 3.0,
+        },
+    );
+    for p in [p + q, p * q] {
+        assert_eq!((p.a, p.r), (PI, 6.0));
+    }
+    let p = Pol { a: 1.0, r: 2.0 } * 1.5;
+    assert_eq!((p.a, p.r), (1.0, 3.0));
+    let p = Pol::from(V2 { x: 0.0, y: -4.0 });
+    assert_eq!((p.a, p.r), (-FRAC_PI_2, 4.0));
+}
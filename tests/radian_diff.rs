@@ -0,0 +1,35 @@
+use maze::radian_diff;
+use proptest::prelude::*;
+use std::f64::consts::{PI, TAU};
+
+const ANGLE: std::ops::Range<f64> = -1000.0..1000.0;
+
+proptest! {
+    #[test]
+    fn symmetric(a in ANGLE, b in ANGLE) {
+        prop_assert_eq!(radian_diff(a, b), radian_diff(b, a));
+    }
+
+    #[test]
+    fn between_zero_and_pi(a in ANGLE, b in ANGLE) {
+        let d = radian_diff(a, b);
+        prop_assert!((0.0..=PI).contains(&d), "radian_diff({}, {}) = {}", a, b, d);
+    }
+
+    #[test]
+    fn same_angle(a in ANGLE) {
+        prop_assert_eq!(radian_diff(a, a), 0.0);
+    }
+
+    // a + TAU is rounded, so this can only hold to within that rounding.
+    #[test]
+    fn periodic(a in ANGLE) {
+        prop_assert!(radian_diff(a, a + TAU) < 1e-12);
+    }
+}
+
+#[test]
+fn test_radian_diff_half_turn() {
+    assert_eq!(radian_diff(0.0, PI), PI);
+    assert_eq!(radian_diff(PI, -PI), 0.0);
+}
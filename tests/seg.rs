@@ -0,0 +1,167 @@
+use maze::seg::*;
+use maze::V2;
+use std::f64::consts::TAU;
+
+#[test]
+fn test_intersection_crossing() {
+    let a = V2 { x: -1.0, y: -1.0 };
+    let b = V2 { x: 1.0, y: 1.0 };
+    let c = V2 { x: -1.0, y: 1.0 };
+    let d = V2 { x: 1.0, y: -1.0 };
+    assert!(intersection(a, b, c, d));
+    assert!(intersection(c, d, a, b));
+}
+
+#[test]
+fn test_intersection_disjoint() {
+    let a = V2 { x: 0.0, y: 0.0 };
+    let b = V2 { x: 1.0, y: 0.0 };
+    let c = V2 { x: 0.0, y: 1.0 };
+    let d = V2 { x: 1.0, y: 1.0 };
+    assert!(!intersection(a, b, c, d));
+}
+
+#[test]
+fn test_intersection_proper_or_touching() {
+    let p = |x, y| V2 { x, y };
+    let both = |a, b, c, d| {
+        let forward = intersection_proper_or_touching(a, b, c, d);
+        assert_eq!(forward, intersection_proper_or_touching(c, d, a, b));
+        assert_eq!(forward, intersection_proper_or_touching(b, a, d, c));
+        forward
+    };
+    // Crossing.
+    assert!(both(p(-1.0, -1.0), p(1.0, 1.0), p(-1.0, 1.0), p(1.0, -1.0)));
+    // Collinear and overlapping, which `intersection` misses.
+    assert!(!intersection(
+        p(0.0, 0.0),
+        p(2.0, 0.0),
+        p(1.0, 0.0),
+        p(3.0, 0.0)
+    ));
+    assert!(both(p(0.0, 0.0), p(2.0, 0.0), p(1.0, 0.0), p(3.0, 0.0)));
+    // One inside the other.
+    assert!(both(p(0.0, 0.0), p(4.0, 4.0), p(1.0, 1.0), p(2.0, 2.0)));
+    // T-shape: cd ends in the middle of ab.
+    assert!(!intersection(
+        p(0.0, 0.0),
+        p(2.0, 0.0),
+        p(1.0, 0.0),
+        p(1.0, 1.0)
+    ));
+    assert!(both(p(0.0, 0.0), p(2.0, 0.0), p(1.0, 0.0), p(1.0, 1.0)));
+    // Sharing an endpoint.
+    assert!(both(p(0.0, 0.0), p(1.0, 0.0), p(1.0, 0.0), p(1.0, 1.0)));
+    // Collinear but apart.
+    assert!(!both(p(0.0, 0.0), p(1.0, 0.0), p(2.0, 0.0), p(3.0, 0.0)));
+    // Parallel and apart.
+    assert!(!both(p(0.0, 0.0), p(1.0, 0.0), p(0.0, 1.0), p(1.0, 1.0)));
+    // On the line through ab, but past its end.
+    assert!(!both(p(0.0, 0.0), p(1.0, 0.0), p(2.0, -1.0), p(2.0, 1.0)));
+}
+
+#[test]
+fn test_displace_by() {
+    let a = V2 { x: 0.0, y: 0.0 };
+    let b = V2 { x: 2.0, y: 0.0 };
+    let (a1, b1) = displace_by(a, b, TAU / 4.0, 3.0);
+    assert!((a1 - V2 { x: 0.0, y: 3.0 }).length() < 1e-9);
+    assert!((b1 - V2 { x: 2.0, y: 3.0 }).length() < 1e-9);
+}
+
+#[test]
+fn test_intersection_with_width() {
+    let a = V2 { x: 0.0, y: 0.0 };
+    let b = V2 { x: 10.0, y: 0.0 };
+    // A segment ending within the tube width collides.
+    let c = V2 { x: 5.0, y: 0.5 };
+    let d = V2 { x: 5.0, y: 5.0 };
+    assert!(intersection_with_width(a, b, c, d, 1.0));
+    // One ending well outside of it does not.
+    let c = V2 { x: 5.0, y: 3.0 };
+    let d = V2 { x: 5.0, y: 5.0 };
+    assert!(!intersection_with_width(a, b, c, d, 1.0));
+}
+
+#[test]
+fn test_intersection_with_width_symmetric() {
+    // Found by the fuzzer: nearly collinear, overlapping and tiny, so the
+    // distance came out on either side of the width depending on the order.
+    let a = V2 {
+        x: 2.470469939758904e-309,
+        y: 0.0,
+    };
+    let b = V2 {
+        x: 3.0517578125e-5,
+        y: 1.390671161567e-309,
+    };
+    let c = V2 {
+        x: 3.2424926757812534e-5,
+        y: 0.0,
+    };
+    let d = V2 {
+        x: 0.0,
+        y: 2.2861189029e-313,
+    };
+    let width = 2.9993936277912626e-241;
+    assert_eq!(
+        intersection_with_width(a, b, c, d, width),
+        intersection_with_width(c, d, a, b, width)
+    );
+}
+
+#[test]
+fn test_segments_min_distance() {
+    let p = |x, y| V2 { x, y };
+    // Crossing.
+    assert_eq!(
+        segments_min_distance(p(-1.0, -1.0), p(1.0, 1.0), p(-1.0, 1.0), p(1.0, -1.0)),
+        0.0
+    );
+    // Parallel and overlapping, parallel and offset end to end.
+    let d = segments_min_distance(p(0.0, 0.0), p(10.0, 0.0), p(2.0, 1.5), p(8.0, 1.5));
+    assert!((d - 1.5).abs() < 1e-12);
+    let d = segments_min_distance(p(0.0, 0.0), p(10.0, 0.0), p(13.0, 4.0), p(20.0, 4.0));
+    assert!((d - 5.0).abs() < 1e-12);
+    // Endpoint to interior, and a degenerate segment.
+    let d = segments_min_distance(p(0.0, 0.0), p(10.0, 0.0), p(5.0, 2.0), p(7.0, 9.0));
+    assert!((d - 2.0).abs() < 1e-12);
+    let d = segments_min_distance(p(3.0, 4.0), p(3.0, 4.0), p(0.0, 0.0), p(0.0, 0.0));
+    assert!((d - 5.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_exact_and_approx_width() {
+    let p = |x, y| V2 { x, y };
+    let both = |a, b, c, d| {
+        (
+            intersection_with_width(a, b, c, d, 1.0),
+            approx_intersection_with_width(a, b, c, d, 1.0, 0.15),
+        )
+    };
+    // Crossing passages and well separated ones agree.
+    assert_eq!(
+        both(p(-5.0, -5.0), p(5.0, 5.0), p(-5.0, 5.0), p(5.0, -5.0)),
+        (true, true)
+    );
+    assert_eq!(
+        both(p(0.0, 0.0), p(10.0, 0.0), p(0.0, 5.0), p(10.0, 5.0)),
+        (false, false)
+    );
+    // Near-parallel passages 1.5 apart overlap, but the short offset copies
+    // never cross.
+    assert_eq!(
+        both(p(0.0, 0.0), p(10.0, 0.0), p(0.0, 1.5), p(10.0, 1.6)),
+        (true, false)
+    );
+    // An end poking into the side of another passage.
+    assert_eq!(
+        both(p(0.0, 0.0), p(10.0, 0.0), p(5.0, 1.8), p(5.0, 8.0)),
+        (true, false)
+    );
+    // Passages sharing a node only collide when they run alongside.
+    let fork = |d| intersection_with_width(p(0.0, 0.0), p(10.0, 0.0), p(0.0, 0.0), d, 1.0);
+    assert!(!fork(p(0.0, 10.0)));
+    assert!(fork(p(10.0, 1.0)));
+    assert!(fork(p(10.0, 0.0)));
+}